@@ -0,0 +1,104 @@
+//! Measures [`Rewriter::rewrite`], small rewrites, which are dominated by
+//! resolving the prelude, and deeply nested groups, run with `cargo bench -p
+//! quote-use-engine`.
+//!
+//! `replace_collect` is the previous approach of collecting a `TokenStream`
+//! per token and group, compared to [`Rewriter::rewrite`] without the
+//! prelude, which produces the same output.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::Instant;
+
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::quote;
+use quote_use_engine::{Directives, Rewriter, Use};
+use syn::Ident;
+
+const ITERATIONS: u32 = 1000;
+
+/// The previous approach, collecting the tokens of each group.
+fn replace_collect(tokens: TokenStream, uses: &HashMap<String, TokenStream>) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| match &token {
+            TokenTree::Ident(ident) => match uses.get(&ident.to_string()) {
+                Some(path) => path.clone(),
+                None => quote!(#token),
+            },
+            TokenTree::Group(group) => {
+                let tokens = replace_collect(group.stream(), uses);
+                match group.delimiter() {
+                    Delimiter::Parenthesis => quote!((#tokens)),
+                    Delimiter::Brace => quote!({#tokens}),
+                    Delimiter::Bracket => quote!([#tokens]),
+                    Delimiter::None => tokens,
+                }
+            }
+            _ => quote!(#token),
+        })
+        .collect()
+}
+
+fn main() {
+    let ident = |name| Ident::new(name, Span::call_site());
+    let uses = vec![
+        Use::new([ident("std"), ident("sync"), ident("Arc")]),
+        Use::new([ident("std"), ident("collections"), ident("HashMap")]),
+    ];
+    let by_name: HashMap<_, _> = uses
+        .iter()
+        .map(|u| (u.name().to_string(), u.path()))
+        .collect();
+    let without_prelude =
+        Rewriter::with_directives(Directives::default().no_prelude(true), uses.clone());
+    let rewriter = Rewriter::with_directives(Directives::default(), uses);
+    let item = quote! {
+        impl Trait for Struct {
+            fn method(&self, value: Arc<HashMap<String, Option<u8>>>) -> Option<Vec<u8>> {
+                let map = HashMap::new();
+                Some(value.iter().map(|(_, value)| value.unwrap_or_default()).collect())
+            }
+        }
+    };
+    let input: TokenStream = (0..100).map(|_| item.clone()).collect();
+
+    assert_eq!(
+        replace_collect(input.clone(), &by_name).to_string(),
+        without_prelude.rewrite(input.clone()).unwrap().to_string()
+    );
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(replace_collect(input.clone(), &by_name));
+    }
+    println!("replace_collect: {:?}", start.elapsed() / ITERATIONS);
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(without_prelude.rewrite(input.clone()).unwrap());
+    }
+    println!(
+        "rewrite without prelude: {:?}",
+        start.elapsed() / ITERATIONS
+    );
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(rewriter.rewrite(input.clone()).unwrap());
+    }
+    println!("rewrite: {:?}", start.elapsed() / ITERATIONS);
+
+    let input = quote!(Some(Arc::new(Vec::new())));
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(rewriter.rewrite(input.clone()).unwrap());
+    }
+    println!("rewrite small: {:?}", start.elapsed() / ITERATIONS);
+
+    let input = (0..200).fold(quote!(Arc::new(0)), |inner, _| quote!(Some([{ (#inner) }])));
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(rewriter.rewrite(input.clone()).unwrap());
+    }
+    println!("rewrite nested: {:?}", start.elapsed() / ITERATIONS);
+}
@@ -0,0 +1,319 @@
+use proc_macro2::TokenStream;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Error, Ident, LitInt, Result, Token};
+
+use crate::Use;
+
+/// Names accepted as `# use <directive>;`.
+pub const DIRECTIVES: &[&str] = &[
+    "no_prelude",
+    "no_std",
+    "strict",
+    "strict_interpolation",
+    "lock_prelude",
+    "prefer_std",
+    "no_2021_prelude",
+    "warn_unused",
+    "no_leading_colon",
+];
+
+/// Configuration set through `# use <directive>;` statements.
+///
+/// ```
+/// # use quote_use_engine::{Directives, Edition};
+/// let directives = Directives::default()
+///     .no_std(true)
+///     .edition(Edition::Edition2024);
+/// ```
+#[derive(Default)]
+pub struct Directives {
+    /// `# use no_prelude;` disabling all preludes.
+    pub(crate) no_prelude: bool,
+    /// `# use no_std;` disabling the `std` prelude.
+    pub(crate) no_std: bool,
+    /// `# use strict;` making unknown directives an error.
+    pub(crate) strict: bool,
+    /// `# use strict_interpolation;` erroring on imports only used as `#var`.
+    pub(crate) strict_interpolation: bool,
+    /// Crate set through `# use dollar_crate(name);`, whose imports are rooted
+    /// at `$crate`.
+    pub(crate) dollar_crate: Option<Ident>,
+    /// `# use lock_prelude;` making the prelude take precedence over imports.
+    pub(crate) lock_prelude: bool,
+    /// `# use prefer_std;` replacing prelude names with their `std` path, even
+    /// when they are part of `core`.
+    pub(crate) prefer_std: bool,
+    /// `# use no_2021_prelude;` disabling the 2021 edition prelude.
+    pub(crate) no_2021_prelude: bool,
+    /// `# use warn_unused;` warning about imports that are never used.
+    pub(crate) warn_unused: bool,
+    /// `# use no_leading_colon;` omitting the leading `::` of paths.
+    pub(crate) no_leading_colon: bool,
+    /// Root of imports set through `# use relative_to(path);`.
+    pub(crate) relative_to: Option<TokenStream>,
+    /// Edition selecting the prelude, set through `# use edition(2021);`.
+    pub(crate) edition: Edition,
+    /// Preludes set through `# use prelude(core, std);`, replacing the
+    /// selection by `no_std`, `no_2021_prelude` and `edition`.
+    pub(crate) preludes: Option<Vec<Prelude>>,
+}
+
+/// Prelude selectable through `# use prelude(core, std);`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Prelude {
+    /// `core`, e.g., `Option`.
+    Core,
+    /// `std`, e.g., `::std::prelude::v1::Vec`.
+    Std,
+    /// `alloc`, e.g., `::alloc::vec::Vec`.
+    Alloc,
+    /// `rust_2021`, e.g., `TryFrom`.
+    Rust2021,
+    /// `rust_2024`, e.g., `Future`.
+    Rust2024,
+}
+
+/// Parses the preludes of `prelude(core, std)`, i.e., `core, std`, or `none`.
+pub(crate) fn parse_preludes(input: ParseStream) -> Result<Vec<Prelude>> {
+    let names = Punctuated::<Ident, Token![,]>::parse_terminated_with(input, Ident::parse_any)?;
+    let mut preludes = Vec::new();
+    for name in &names {
+        preludes.push(match name.to_string().as_str() {
+            "none" if names.len() == 1 => continue,
+            "none" => {
+                return Err(Error::new(
+                    name.span(),
+                    "`none` cannot be combined with other preludes",
+                ));
+            }
+            "core" => Prelude::Core,
+            "alloc" => Prelude::Alloc,
+            "std" => Prelude::Std,
+            "rust_2021" => Prelude::Rust2021,
+            "rust_2024" => Prelude::Rust2024,
+            _ => {
+                return Err(Error::new(
+                    name.span(),
+                    "unknown prelude, expected one of `core`, `alloc`, `std`, `rust_2021`, \
+                     `rust_2024` or `none`",
+                ));
+            }
+        });
+    }
+    Ok(preludes)
+}
+
+/// Rust edition, selecting the edition specific prelude.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Edition {
+    Edition2015,
+    Edition2018,
+    #[default]
+    Edition2021,
+    Edition2024,
+}
+
+impl Parse for Edition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let edition: LitInt = input.parse()?;
+        Ok(match edition.base10_parse::<u16>()? {
+            2015 => Self::Edition2015,
+            2018 => Self::Edition2018,
+            2021 => Self::Edition2021,
+            2024 => Self::Edition2024,
+            _ => {
+                return Err(Error::new_spanned(
+                    edition,
+                    "unknown edition, expected one of 2015, 2018, 2021 or 2024",
+                ));
+            }
+        })
+    }
+}
+
+impl Directives {
+    /// Disables all preludes, like `# use no_prelude;`.
+    #[must_use]
+    pub fn no_prelude(mut self, no_prelude: bool) -> Self {
+        self.no_prelude = no_prelude;
+        self
+    }
+
+    /// Disables the `std` prelude, like `# use no_std;`.
+    #[must_use]
+    pub fn no_std(mut self, no_std: bool) -> Self {
+        self.no_std = no_std;
+        self
+    }
+
+    /// Makes unknown directives an error, like `# use strict;`.
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Errors on imports only used as `#var`, like `# use
+    /// strict_interpolation;`.
+    #[must_use]
+    pub fn strict_interpolation(mut self, strict_interpolation: bool) -> Self {
+        self.strict_interpolation = strict_interpolation;
+        self
+    }
+
+    /// Roots imports of the crate `name` at `$crate`, like `# use
+    /// dollar_crate(name);`.
+    #[must_use]
+    pub fn dollar_crate(mut self, name: Ident) -> Self {
+        self.dollar_crate = Some(name);
+        self
+    }
+
+    /// Makes the prelude take precedence over imports, like `# use
+    /// lock_prelude;`.
+    #[must_use]
+    pub fn lock_prelude(mut self, lock_prelude: bool) -> Self {
+        self.lock_prelude = lock_prelude;
+        self
+    }
+
+    /// Uses the `std` path for prelude names that are part of `core` as well,
+    /// like `# use prefer_std;`.
+    #[must_use]
+    pub fn prefer_std(mut self, prefer_std: bool) -> Self {
+        self.prefer_std = prefer_std;
+        self
+    }
+
+    /// Disables the 2021 edition prelude, like `# use no_2021_prelude;`.
+    #[must_use]
+    pub fn no_2021_prelude(mut self, no_2021_prelude: bool) -> Self {
+        self.no_2021_prelude = no_2021_prelude;
+        self
+    }
+
+    /// Warns about imports that are never used, like `# use warn_unused;`.
+    #[must_use]
+    pub fn warn_unused(mut self, warn_unused: bool) -> Self {
+        self.warn_unused = warn_unused;
+        self
+    }
+
+    /// Omits the leading `::` of paths, like `# use no_leading_colon;`.
+    #[must_use]
+    pub fn no_leading_colon(mut self, no_leading_colon: bool) -> Self {
+        self.no_leading_colon = no_leading_colon;
+        self
+    }
+
+    /// Sets the root of imports, like `# use relative_to(path);`.
+    #[must_use]
+    pub fn relative_to(mut self, root: TokenStream) -> Self {
+        self.relative_to = Some(root);
+        self
+    }
+
+    /// Sets the edition selecting the prelude, like `# use edition(2021);`.
+    #[must_use]
+    pub fn edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    /// Selects the preludes explicitly, like `# use prelude(core, std);`.
+    #[must_use]
+    pub fn preludes(mut self, preludes: Vec<Prelude>) -> Self {
+        self.preludes = Some(preludes);
+        self
+    }
+
+    /// Extracts the directives from single ident imports (`# use ident;`).
+    ///
+    /// Unknown names are imports of crates, unless `strict` is set, then they
+    /// are an error.
+    pub(crate) fn extract(candidates: Vec<Use>, uses: &mut Vec<Use>) -> Result<Self> {
+        let mut directives = Self {
+            strict: candidates.iter().any(|u| u.1 == "strict"),
+            ..Self::default()
+        };
+        for candidate in candidates {
+            let ident = &candidate.1;
+            let name = ident.to_string();
+            match name.as_str() {
+                "no_prelude" => directives.no_prelude = true,
+                "no_std" => directives.no_std = true,
+                "strict" => {}
+                "strict_interpolation" => directives.strict_interpolation = true,
+                "dollar_crate" => {
+                    return Err(Error::new(
+                        ident.span(),
+                        "`dollar_crate` needs the name of the crate, e.g., `# use \
+                         dollar_crate(my_crate);`",
+                    ));
+                }
+                "lock_prelude" => directives.lock_prelude = true,
+                "prefer_std" => directives.prefer_std = true,
+                "no_2021_prelude" => directives.no_2021_prelude = true,
+                "warn_unused" => directives.warn_unused = true,
+                "no_leading_colon" => directives.no_leading_colon = true,
+                _ if directives.strict => {
+                    return Err(match DIRECTIVES.iter().min_by_key(|d| distance(d, &name)) {
+                        Some(similar) if distance(similar, &name) <= 2 => Error::new(
+                            ident.span(),
+                            format_args!("unknown directive `{ident}`, did you mean `{similar}`?"),
+                        ),
+                        _ => Error::new(
+                            ident.span(),
+                            format_args!(
+                                "unknown directive `{ident}`, use `# use ::{ident};` to import a \
+                                 crate"
+                            ),
+                        ),
+                    });
+                }
+                _ => uses.push(candidate),
+            }
+        }
+        Ok(directives)
+    }
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diagonal + usize::from(a != *b))
+                .min(row[j] + 1)
+                .min(above + 1);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use proc_macro2::Span;
+    use syn::Ident;
+
+    use super::*;
+
+    #[test]
+    fn directives() {
+        for directive in DIRECTIVES {
+            let mut uses = Vec::new();
+            let candidate = Use::new([Ident::new(directive, Span::call_site())]);
+            Directives::extract(vec![candidate], &mut uses).unwrap();
+            assert!(uses.is_empty(), "`{directive}` is not handled");
+        }
+    }
+}
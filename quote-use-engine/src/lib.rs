@@ -0,0 +1,1061 @@
+//! Implementation of [`quote-use`](https://docs.rs/quote-use/), shared by its
+//! proc-macros and its runtime API.
+//!
+//! This crate is internal, use [`quote-use`](https://docs.rs/quote-use/)
+//! instead.
+extern crate proc_macro;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::{env, fs};
+
+use proc_macro2::{Delimiter, Group, Spacing, Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::token::{Brace, Bracket, Paren};
+use syn::{Error, Ident, LitStr, Result, Token, parenthesized};
+use use_parser::UseItem;
+
+mod directive;
+
+mod prelude;
+
+mod use_parser;
+
+pub use directive::{DIRECTIVES, Directives, Edition, Prelude};
+pub use use_parser::Use;
+
+mod kw {
+    syn::custom_keyword!(dollar_crate);
+    syn::custom_keyword!(edition);
+    syn::custom_keyword!(group);
+    syn::custom_keyword!(include);
+    syn::custom_keyword!(keep);
+    syn::custom_keyword!(prelude);
+    syn::custom_keyword!(relative_to);
+    syn::custom_keyword!(value);
+}
+
+/// `# macro NAME = tokens;`, replacing `NAME` with `tokens`.
+#[derive(Clone)]
+struct Macro(Ident, TokenStream);
+
+impl Parse for Macro {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<Token![macro]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let mut tokens = TokenStream::new();
+        while !input.is_empty() && !input.peek(Token![;]) {
+            tokens.extend([input.parse::<TokenTree>()?]);
+        }
+        input.parse::<Token![;]>()?;
+        Ok(Self(name, tokens))
+    }
+}
+
+/// Input of `quote_use!`, i.e., `# use` statements followed by tokens.
+pub struct QuoteUse {
+    rewriter: Rewriter,
+    /// Files read through `# use include("file");`.
+    includes: Vec<PathBuf>,
+    tail: TokenStream,
+}
+
+/// Applies imports to tokens, without parsing `# use` statements.
+#[derive(Default)]
+pub struct Rewriter {
+    directives: Directives,
+    uses: Vec<Use>,
+    /// `# use type a::Foo;` and `# use value b::Foo;`.
+    namespaced: Vec<(Namespace, Use)>,
+    macros: Vec<Macro>,
+    /// Names kept as is, set through `# use keep Name;`.
+    kept: Vec<Ident>,
+    /// `# use prelude a::{B, C};`, taking precedence over the prelude, but not
+    /// over imports.
+    prelude: Vec<Use>,
+}
+
+impl Parse for QuoteUse {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut uses = Vec::new();
+        let mut namespaced = Vec::new();
+        let mut directives = Vec::new();
+        let mut macros: Vec<Macro> = Vec::new();
+        let mut kept = Vec::new();
+        let mut prelude = Vec::new();
+        let mut includes = Vec::new();
+        let mut relative_to = None;
+        let mut dollar_crate = None;
+        let mut edition = None;
+        let mut preludes = None;
+        let mut groups: Vec<(Ident, Vec<Use>)> = Vec::new();
+        while input.peek(Token![#])
+            && (input.peek2(Token![use])
+                || input.peek2(Token![pub])
+                || input.peek2(Token![macro])
+                || input.peek2(Token![extern])
+                || peek_attributed_use(input))
+        {
+            input.parse::<Token![#]>().expect("# was peeked before");
+            if input.peek(Token![#]) {
+                // `# #[cfg(..)] use a::B;`
+                let pound = input.parse::<Token![#]>()?;
+                let attribute = input.parse::<TokenTree>()?;
+                return Err(Error::new_spanned(
+                    quote!(#pound #attribute),
+                    "attributes on `# use` are not supported, as imports are replaced instead of \
+                     emitted, i.e., a `#[cfg]` cannot be evaluated; use separate `quote_use!` \
+                     invocations instead",
+                ));
+            } else if input.peek(Token![macro]) {
+                macros.push(input.parse()?);
+            } else if input.peek2(kw::keep) && input.peek3(Ident) {
+                // `use keep a, b;`
+                input.parse::<Token![use]>()?;
+                input.parse::<kw::keep>()?;
+                loop {
+                    kept.push(input.call(Ident::parse_any)?);
+                    if input.parse::<Option<Token![,]>>()?.is_none() {
+                        break;
+                    }
+                }
+                input.parse::<Token![;]>()?;
+            } else if input.peek2(kw::include) && input.peek3(Paren) {
+                // `use include("file");`
+                input.parse::<Token![use]>()?;
+                input.parse::<kw::include>()?;
+                let content;
+                parenthesized!(content in input);
+                let file: LitStr = content.parse()?;
+                let span = file.span();
+                let file = include_path(&file);
+                input.parse::<Token![;]>()?;
+                uses.extend(include(&file, span)?);
+                includes.push(fs::canonicalize(&file).unwrap_or(file));
+            } else if input.peek2(kw::relative_to) && input.peek3(Paren) {
+                // `use relative_to(path);`
+                input.parse::<Token![use]>()?;
+                let keyword = input.parse::<kw::relative_to>()?;
+                let content;
+                parenthesized!(content in input);
+                input.parse::<Token![;]>()?;
+                if relative_to.replace(content.parse()?).is_some() {
+                    return Err(Error::new(
+                        keyword.span,
+                        "`relative_to` is specified multiple times",
+                    ));
+                }
+            } else if input.peek2(kw::dollar_crate) && input.peek3(Paren) {
+                // `use dollar_crate(name);`
+                input.parse::<Token![use]>()?;
+                let keyword = input.parse::<kw::dollar_crate>()?;
+                let content;
+                parenthesized!(content in input);
+                input.parse::<Token![;]>()?;
+                if dollar_crate.replace(content.parse()?).is_some() {
+                    return Err(Error::new(
+                        keyword.span,
+                        "`dollar_crate` is specified multiple times",
+                    ));
+                }
+            } else if input.peek2(kw::edition) && input.peek3(Paren) {
+                // `use edition(2021);`
+                input.parse::<Token![use]>()?;
+                let keyword = input.parse::<kw::edition>()?;
+                let content;
+                parenthesized!(content in input);
+                input.parse::<Token![;]>()?;
+                if edition.replace(content.parse()?).is_some() {
+                    return Err(Error::new(
+                        keyword.span,
+                        "`edition` is specified multiple times",
+                    ));
+                }
+            } else if input.peek2(kw::prelude) && input.peek3(Paren) {
+                // `use prelude(core, std);`
+                input.parse::<Token![use]>()?;
+                let keyword = input.parse::<kw::prelude>()?;
+                let content;
+                parenthesized!(content in input);
+                input.parse::<Token![;]>()?;
+                if preludes
+                    .replace(directive::parse_preludes(&content)?)
+                    .is_some()
+                {
+                    return Err(Error::new(
+                        keyword.span,
+                        "`prelude` is specified multiple times",
+                    ));
+                }
+            } else if input.peek2(kw::group) && input.peek3(Ident) {
+                // `use group Name = {a::B, c::D};`
+                input.parse::<Token![use]>()?;
+                input.parse::<kw::group>()?;
+                let name: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                if !input.peek(Brace) {
+                    return Err(input.error("expected `{`"));
+                }
+                let group = UseItem::parse_tree(input)?.0;
+                input.parse::<Token![;]>()?;
+                groups.push((name, group));
+            } else if input.peek2(kw::prelude)
+                && (input.peek3(Ident)
+                    || input.peek3(Brace)
+                    || input.peek3(Token![crate])
+                    || input.peek3(Token![super]))
+            {
+                // `use prelude a::{B, C};`
+                input.parse::<Token![use]>()?;
+                input.parse::<kw::prelude>()?;
+                prelude.extend(UseItem::parse_tree(input)?.0);
+                input.parse::<Token![;]>()?;
+            } else if input.peek2(Token![type]) || input.peek2(kw::value) && input.peek3(Ident) {
+                // `use type a::Foo;` and `use value b::Foo;`
+                input.parse::<Token![use]>()?;
+                let namespace = if input.parse::<Option<Token![type]>>()?.is_some() {
+                    Namespace::Type
+                } else {
+                    input.parse::<kw::value>()?;
+                    Namespace::Value
+                };
+                let item = UseItem::parse_tree(input)?.0;
+                input.parse::<Token![;]>()?;
+                namespaced.extend(item.into_iter().map(|item| (namespace, item)));
+            } else if input.peek2(Ident) && input.peek3(Token![;]) {
+                // `use ident;`
+                let item = UseItem::parse(input)?.0;
+                if let Some((_, group)) = groups.iter().find(|(name, _)| name == &item[0].1) {
+                    uses.extend(group.iter().cloned());
+                } else {
+                    directives.extend(item);
+                }
+            } else {
+                uses.extend(UseItem::parse(input)?.0);
+            }
+        }
+        let mut directives = Directives::extract(directives, &mut uses)?;
+        directives.relative_to = relative_to;
+        directives.dollar_crate = dollar_crate;
+        directives.edition = edition.unwrap_or_default();
+        directives.preludes = preludes;
+
+        let names: Vec<_> = uses
+            .iter()
+            .map(|u| (&u.1, None))
+            .chain(
+                namespaced
+                    .iter()
+                    .map(|(namespace, u)| (&u.1, Some(*namespace))),
+            )
+            .chain(macros.iter().map(|m| (&m.0, None)))
+            .collect();
+        for (i, (ident, namespace)) in names.iter().enumerate() {
+            // The same name can be imported once per namespace.
+            if let Some((previous, _)) = names[..i].iter().find(|(previous, previous_namespace)| {
+                same_name(previous, ident)
+                    && (namespace.is_none()
+                        || previous_namespace.is_none()
+                        || namespace == previous_namespace)
+            }) {
+                let mut error = Error::new(
+                    ident.span(),
+                    format_args!("the name `{ident}` is imported multiple times"),
+                );
+                error.combine(Error::new(
+                    previous.span(),
+                    format_args!("previous import of `{ident}` here"),
+                ));
+                return Err(error);
+            }
+        }
+
+        Ok(QuoteUse {
+            rewriter: Rewriter {
+                directives,
+                uses,
+                namespaced,
+                macros,
+                kept,
+                prelude,
+            },
+            includes,
+            tail: input.parse()?,
+        })
+    }
+}
+
+/// Resolves `file` relative to the invoking source file when possible,
+/// otherwise relative to the crate root.
+fn include_path(file: &LitStr) -> PathBuf {
+    let source_dir = proc_macro::is_available()
+        .then(|| file.span().unwrap().local_file())
+        .flatten()
+        .and_then(|source| source.parent().map(ToOwned::to_owned))
+        .or_else(|| env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from))
+        .unwrap_or_default();
+    source_dir.join(file.value())
+}
+
+/// Reads the `use` statements in `file`, erroring at `span`.
+fn include(file: &std::path::Path, span: Span) -> Result<Vec<Use>> {
+    let content = fs::read_to_string(file).map_err(|err| {
+        Error::new(
+            span,
+            format_args!("failed to read `{}`: {err}", file.display()),
+        )
+    })?;
+    let parser = |input: ParseStream| {
+        let mut uses = Vec::new();
+        while !input.is_empty() {
+            uses.extend(UseItem::parse(input)?.0);
+        }
+        Ok(uses)
+    };
+    parser.parse_str(&content).map_err(|err| {
+        Error::new(
+            span,
+            format_args!("failed to parse `{}`: {err}", file.display()),
+        )
+    })
+}
+
+impl QuoteUse {
+    /// Returns how often each import was replaced, see
+    /// [`Rewriter::substitution_stats`].
+    pub fn substitution_stats(&self) -> HashMap<Ident, usize> {
+        self.rewriter.substitution_stats(self.tail.clone())
+    }
+
+    /// Returns the applied imports, see [`Rewriter::applied_imports`].
+    pub fn applied_imports(&self) -> Vec<(Ident, TokenStream)> {
+        self.rewriter.applied_imports(self.tail.clone())
+    }
+
+    /// Returns an item emitting a warning listing the applied imports, used
+    /// by `quote_use_debug!`.
+    pub fn debug_imports(&self) -> TokenStream {
+        let imports: Vec<_> = self
+            .applied_imports()
+            .into_iter()
+            .map(|(name, path)| format!("`{name}` -> `{}`", path.to_string().replace(' ', "")))
+            .collect();
+        let note = if imports.is_empty() {
+            "no imports applied".to_owned()
+        } else {
+            format!("applied imports: {}", imports.join(", "))
+        };
+        warning(&Ident::new("quote_use_debug", Span::call_site()), note)
+    }
+
+    /// Returns the items to emit before the quote macro, and its body.
+    pub fn expand(&self) -> Result<(TokenStream, TokenStream)> {
+        let (warnings, tokens) = self.rewriter.expand(self.tail.clone())?;
+
+        // Recompile when an included file changes.
+        let includes = self.includes.iter().map(|file| file.display().to_string());
+        let mut items = quote!(#(const _: &[u8] = ::core::include_bytes!(#includes);)*);
+        items.extend(warnings);
+
+        Ok((items, tokens))
+    }
+}
+
+impl Rewriter {
+    /// Creates a rewriter applying `uses`, configured by `directives`.
+    pub fn with_directives(directives: Directives, uses: Vec<Use>) -> Self {
+        Self {
+            directives,
+            uses,
+            ..Self::default()
+        }
+    }
+
+    /// Replaces the imported names in `tokens`.
+    ///
+    /// # Errors
+    ///
+    /// Errors when a directive is violated, e.g., `strict_interpolation`.
+    pub fn rewrite(&self, tokens: TokenStream) -> Result<TokenStream> {
+        // Warnings can only be emitted by proc-macros.
+        let (_warnings, tokens) = self.expand(tokens)?;
+        Ok(tokens)
+    }
+
+    /// Returns how often each import was replaced in `tokens`.
+    pub fn substitution_stats(&self, tokens: TokenStream) -> HashMap<Ident, usize> {
+        let (uses, _, namespaced) = self.resolved_uses();
+        let mut replacer = Replacer::new(self, &uses, &namespaced);
+        replacer.replace_in_group(tokens, Delimiter::None, false);
+        replacer.used
+    }
+
+    /// Returns the imports applied in `tokens`, i.e., the replaced names and
+    /// their paths, sorted by name.
+    pub fn applied_imports(&self, tokens: TokenStream) -> Vec<(Ident, TokenStream)> {
+        let (uses, _, namespaced) = self.resolved_uses();
+        let mut replacer = Replacer::new(self, &uses, &namespaced);
+        replacer.replace_in_group(tokens, Delimiter::None, false);
+        let mut imports: Vec<_> = replacer
+            .namespaced
+            .iter()
+            .map(|((_, name), path)| (name, *path))
+            .chain(replacer.uses.iter().map(|(name, path)| (name, *path)))
+            .filter(|(name, _)| replacer.used.contains_key(*name))
+            .map(|(name, path)| (name.clone(), path.to_token_stream()))
+            .collect();
+        imports.sort_by_cached_key(|(name, path)| (name.to_string(), path.to_string()));
+        imports.dedup_by(|a, b| a.0 == b.0 && a.1.to_string() == b.1.to_string());
+        imports
+    }
+
+    /// Returns the imports with the directives applied, followed by the
+    /// custom and default prelude, the number of imports before the prelude,
+    /// and the namespaced imports.
+    fn resolved_uses(&self) -> (Vec<Use>, usize, Vec<(Namespace, Use)>) {
+        let Self {
+            directives,
+            uses,
+            namespaced,
+            prelude: custom_prelude,
+            ..
+        } = self;
+        let mut uses = uses.clone();
+        let mut namespaced = namespaced.clone();
+        let mut custom_prelude = custom_prelude.clone();
+        for Use(path, _) in uses
+            .iter_mut()
+            .chain(&mut custom_prelude)
+            .chain(namespaced.iter_mut().map(|(_, u)| u))
+        {
+            if let Some(root) = &directives.relative_to {
+                path.set_root(root.clone());
+            }
+            if let Some(name) = &directives.dollar_crate {
+                path.replace_first(&name.to_string(), quote!($crate));
+            }
+        }
+        let prelude: Vec<_> = if directives.no_prelude {
+            Vec::new()
+        } else {
+            let mut prelude: Vec<_> = prelude::prelude(directives).collect();
+            if directives.prefer_std && !directives.no_std {
+                for Use(path, _) in &mut prelude {
+                    path.rename_first("core", "std");
+                }
+            }
+            prelude
+        };
+        if directives.lock_prelude {
+            let in_prelude = |name: &Ident| prelude.iter().any(|u| same_name(&u.1, name));
+            custom_prelude.retain(|u| !in_prelude(&u.1));
+            let in_prelude = |name: &Ident| {
+                in_prelude(name) || custom_prelude.iter().any(|u| same_name(&u.1, name))
+            };
+            uses.retain(|u| !in_prelude(&u.1));
+            namespaced.retain(|(_, u)| !in_prelude(&u.1));
+        }
+        let user_uses = uses.len();
+        uses.extend(custom_prelude);
+        uses.extend(prelude);
+        if directives.no_leading_colon {
+            for Use(path, _) in uses.iter_mut().chain(namespaced.iter_mut().map(|(_, u)| u)) {
+                path.strip_leading_colons();
+            }
+        }
+        (uses, user_uses, namespaced)
+    }
+
+    /// Returns items emitting warnings, and `tokens` with the imports
+    /// applied.
+    pub fn expand(&self, tokens: TokenStream) -> Result<(TokenStream, TokenStream)> {
+        let Self {
+            directives, macros, ..
+        } = self;
+        let (uses, user_uses, namespaced) = self.resolved_uses();
+
+        let mut replacer = Replacer::new(self, &uses, &namespaced);
+        let tokens = replacer.replace_in_group(tokens, Delimiter::None, false);
+
+        if directives.strict_interpolation {
+            let mut errors = replacer
+                .interpolated
+                .iter()
+                .filter(|var| {
+                    uses[..user_uses].iter().any(|u| same_name(&u.1, var))
+                        && !replacer.used.contains_key(&var.unraw())
+                })
+                .map(|var| {
+                    Error::new(
+                        var.span(),
+                        format_args!(
+                            "`{var}` is only used as interpolation `#{var}`, imports are not \
+                             applied to interpolated tokens"
+                        ),
+                    )
+                });
+            if let Some(mut error) = errors.next() {
+                error.extend(errors);
+                return Err(error);
+            }
+        }
+
+        let mut items = TokenStream::new();
+
+        // Warn about definitions shadowing the prelude.
+        for (namespace, name) in &replacer.defined {
+            if macros.iter().any(|m| same_name(&m.0, name))
+                || !uses[user_uses..]
+                    .iter()
+                    .any(|u| same_name(&u.1, name) && prelude_namespace(name) == Some(*namespace))
+                || uses[..user_uses].iter().any(|u| same_name(&u.1, name))
+                || namespaced.iter().any(|(_, u)| same_name(&u.1, name))
+            {
+                continue;
+            }
+            items.extend(warning(
+                name,
+                format!(
+                    "`{name}` shadows the prelude, but other uses of `{name}` in `quote_use!` \
+                     still refer to the prelude"
+                ),
+            ));
+        }
+
+        // Single uppercase letters are commonly generic parameters, which would be
+        // replaced as well, but also constants.
+        if directives.strict {
+            for Use(_, name) in &uses[..user_uses] {
+                let name_string = name.to_string();
+                if name_string.len() == 1 && name_string.starts_with(char::is_uppercase) {
+                    return Err(Error::new(
+                        name.span(),
+                        format!(
+                            "`{name}` is commonly a generic parameter, which would be replaced as \
+                             well; use a longer alias, e.g., `as {name}Type`"
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // With `lock_prelude`, imports of prelude names are ignored.
+        if directives.lock_prelude {
+            for Use(_, name) in self
+                .uses
+                .iter()
+                .chain(self.namespaced.iter().map(|(_, u)| u))
+            {
+                if uses[user_uses..].iter().any(|u| same_name(&u.1, name)) {
+                    items.extend(warning(
+                        name,
+                        format!(
+                            "`{name}` is part of the prelude, which takes precedence over imports \
+                             with `lock_prelude`"
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // Imports are replaced, not emitted, i.e., traits only used for method
+        // calls are not in scope.
+        for Use(path, name) in &uses[..user_uses] {
+            if replacer.used.contains_key(&name.unraw())
+                || !METHOD_TRAITS
+                    .iter()
+                    .any(|method_trait| path.is(method_trait))
+            {
+                continue;
+            }
+            items.extend(warning(
+                name,
+                format!(
+                    "`{name}` is not brought into scope, as `quote_use!` only replaces the name \
+                     `{name}`; call trait methods through the trait instead, e.g., \
+                     `{name}::method(value)`"
+                ),
+            ));
+        }
+
+        // Imports that never match, e.g., due to a typo.
+        if directives.warn_unused {
+            for Use(path, name) in uses[..user_uses]
+                .iter()
+                .chain(namespaced.iter().map(|(_, u)| u))
+            {
+                // Unused method traits are warned about above.
+                if replacer.used.contains_key(&name.unraw())
+                    || METHOD_TRAITS
+                        .iter()
+                        .any(|method_trait| path.is(method_trait))
+                {
+                    continue;
+                }
+                items.extend(warning(
+                    name,
+                    format!("`{name}` is imported, but never used"),
+                ));
+            }
+        }
+
+        Ok((items, tokens))
+    }
+}
+
+/// Whether `tokens` end with a repetition, i.e., `#(...)` optionally followed
+/// by a separator.
+fn ends_with_repetition(tokens: &[TokenTree]) -> bool {
+    let repetition = |tokens: &[TokenTree]| {
+        matches!(
+            tokens,
+            [.., TokenTree::Punct(pound), TokenTree::Group(group)]
+                if pound.as_char() == '#' && group.delimiter() == Delimiter::Parenthesis
+        )
+    };
+    repetition(tokens)
+        || matches!(
+            tokens.split_last(),
+            Some((separator, rest))
+                if repetition(rest)
+                    && !matches!(separator, TokenTree::Punct(punct) if punct.as_char() == '*')
+        )
+}
+
+/// Whether `input` starts with an import with attributes, i.e., `# #[attr]
+/// use`.
+fn peek_attributed_use(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<Token![#]>().is_ok() && fork.peek(Token![#]) && fork.peek2(Bracket) && {
+        while fork.peek(Token![#]) && fork.peek2(Bracket) {
+            fork.parse::<Token![#]>().expect("# was peeked before");
+            fork.parse::<TokenTree>().expect("[..] was peeked before");
+        }
+        fork.peek(Token![use])
+    }
+}
+
+/// Traits whose imports are commonly only used to call their methods, e.g.,
+/// through `write!`.
+const METHOD_TRAITS: &[&str] = &[
+    "core::fmt::Write",
+    "core::hash::Hash",
+    "core::hash::Hasher",
+    "std::fmt::Write",
+    "std::hash::Hash",
+    "std::hash::Hasher",
+    "std::io::BufRead",
+    "std::io::Read",
+    "std::io::Seek",
+    "std::io::Write",
+];
+
+/// Emits a warning with `note` at `name`, as stable proc-macros cannot emit
+/// warnings, this uses a deprecated item.
+fn warning(name: &Ident, note: String) -> TokenStream {
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            #[allow(non_camel_case_types)]
+            struct #name;
+            let _ = #name;
+        };
+    }
+}
+
+/// Namespace of an import, see [`TypePosition`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Namespace {
+    Type,
+    Value,
+}
+
+/// Whether `a` and `b` are the same name, i.e., `r#a` is the same as `a`.
+fn same_name(a: &Ident, b: &Ident) -> bool {
+    a.unraw() == b.unraw()
+}
+
+/// Namespace of the prelude entry `name`, `None` for macros, e.g., `test`.
+///
+/// This is a heuristic based on the naming conventions, e.g., a custom
+/// prelude function `# use prelude a::function;` is considered a macro.
+fn prelude_namespace(name: &Ident) -> Option<Namespace> {
+    if ["Some", "None", "Ok", "Err", "drop"]
+        .iter()
+        .any(|value| name == value)
+    {
+        Some(Namespace::Value)
+    } else if name.to_string().starts_with(char::is_uppercase) {
+        Some(Namespace::Type)
+    } else {
+        None
+    }
+}
+
+/// Tracks whether tokens are in type position, e.g., after `:` or `->`.
+///
+/// This is a heuristic, as the tokens are not parsed, e.g., the values of
+/// struct expressions `Struct { field: value }` are in type position.
+#[derive(Default)]
+struct TypePosition {
+    ty: bool,
+    /// Depth of `<...>` in type position.
+    angle: usize,
+    /// The tokens are types separated by `,`, e.g., a tuple type.
+    list: bool,
+    previous: Option<char>,
+}
+
+impl TypePosition {
+    /// Returns whether `token` is in type position.
+    fn next(&mut self, token: &TokenTree) -> bool {
+        let ty = self.ty;
+        let previous = self.previous.take();
+        match token {
+            TokenTree::Punct(punct) => match punct.as_char() {
+                // path separator `::`
+                ':' if punct.spacing() == Spacing::Joint => self.previous = Some(':'),
+                ':' if previous == Some(':') => self.previous = Some('p'),
+                ':' => self.ty = true,
+                '-' if punct.spacing() == Spacing::Joint => self.previous = Some('-'),
+                // return type `-> Type`
+                '>' if previous == Some('-') => self.ty = true,
+                // generics `Type<T>` and turbofish `::<T>`
+                '<' if ty || previous == Some('p') => {
+                    self.angle += 1;
+                    self.ty = true;
+                }
+                '>' if self.angle > 0 => self.angle -= 1,
+                ',' => self.ty = self.list || self.angle > 0,
+                '&' | '*' | '\'' | '+' => {}
+                _ => {
+                    self.ty = false;
+                    self.angle = 0;
+                }
+            },
+            TokenTree::Ident(ident) if ident == "as" || ident == "impl" || ident == "dyn" => {
+                self.ty = true;
+            }
+            TokenTree::Ident(_) | TokenTree::Group(_) => {}
+            TokenTree::Literal(_) => self.ty = false,
+        }
+        ty
+    }
+}
+
+struct Replacer<'a> {
+    /// Paths by name without `r#`, the first import of a name takes precedence.
+    uses: HashMap<Ident, &'a use_parser::Path>,
+    namespaced: HashMap<(Namespace, Ident), &'a use_parser::Path>,
+    macros: &'a [Macro],
+    kept: &'a [Ident],
+    /// Names that were replaced, and how often.
+    used: HashMap<Ident, usize>,
+    /// Interpolated variables, i.e., `#var`.
+    interpolated: Vec<Ident>,
+    /// Names of top-level items, e.g., `struct Name`, and their namespace.
+    defined: Vec<(Namespace, Ident)>,
+    /// Number of groups `replace_in_group` is in.
+    depth: usize,
+}
+
+impl<'a> Replacer<'a> {
+    /// Creates a replacer for `rewriter`, with its imports resolved to `uses`
+    /// and `namespaced`.
+    fn new(rewriter: &'a Rewriter, uses: &'a [Use], namespaced: &'a [(Namespace, Use)]) -> Self {
+        let mut uses_by_name = HashMap::new();
+        for Use(path, name) in uses {
+            uses_by_name.entry(name.unraw()).or_insert(path);
+        }
+        let mut namespaced_by_name = HashMap::new();
+        for (namespace, Use(path, name)) in namespaced {
+            namespaced_by_name
+                .entry((*namespace, name.unraw()))
+                .or_insert(path);
+        }
+        Self {
+            uses: uses_by_name,
+            namespaced: namespaced_by_name,
+            macros: &rewriter.macros,
+            kept: &rewriter.kept,
+            used: HashMap::new(),
+            interpolated: Vec::new(),
+            defined: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// Replaces the imported names in `tokens`, the content of a group
+    /// delimited by `delimiter`, `ty` is whether `tokens` start in type
+    /// position.
+    fn replace_in_group(
+        &mut self,
+        tokens: TokenStream,
+        delimiter: Delimiter,
+        ty: bool,
+    ) -> TokenStream {
+        use State::*;
+        #[derive(Clone, Copy)]
+        enum State {
+            Path,
+            Pound,
+            Normal,
+            /// After `*`, i.e., `const`/`mut` are part of a raw pointer type.
+            Pointer,
+            /// After `const`/`static`, the next ident is the item's name.
+            Binding,
+            /// After `'`, i.e., the next ident is a lifetime or label.
+            Lifetime,
+            /// After `.`, i.e., the next ident is a method or field.
+            Dot,
+            /// After the first `.` of `..`.
+            Range,
+            /// After `struct`/`enum`/`fn`/..., the next ident is the item's
+            /// name in the namespace.
+            Definition(Namespace),
+        }
+        let mut state = Normal;
+        let mut position = TypePosition {
+            ty,
+            list: ty,
+            ..TypePosition::default()
+        };
+        // Bounds `where Type: Bound` are no field names.
+        let mut where_clause = false;
+        let tokens: Vec<_> = tokens.into_iter().collect();
+
+        let mut output = TokenStream::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            let ty = position.next(token);
+            match token {
+                TokenTree::Ident(ident) if ident == "where" => where_clause = true,
+                TokenTree::Punct(punct) if punct.as_char() == ';' => where_clause = false,
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                    where_clause = false;
+                }
+                _ => {}
+            }
+            let colon = matches!(
+                tokens.get(i + 1),
+                Some(TokenTree::Punct(punct))
+                    if punct.spacing() == Spacing::Alone && punct.as_char() == ':'
+            );
+            // `const` not followed by `NAME:`, e.g., `const fn` or the `asm!` operand
+            // `const EXPR`.
+            if matches!(token, TokenTree::Ident(ident) if ident != "mut" && !colon)
+                && matches!(state, Binding)
+            {
+                state = Normal;
+            }
+            match (token, state) {
+                // `const NAME: Type` and `static mut NAME: Type`
+                (TokenTree::Ident(ident), Normal) if ident == "const" || ident == "static" => {
+                    state = Binding;
+                }
+                (TokenTree::Ident(ident), Normal) if ident == "fn" => {
+                    state = Definition(Namespace::Value);
+                }
+                (TokenTree::Ident(ident), Normal)
+                    if ["struct", "enum", "union", "type", "trait", "mod"]
+                        .iter()
+                        .any(|keyword| ident == keyword) =>
+                {
+                    state = Definition(Namespace::Type);
+                }
+                (TokenTree::Ident(ident), Definition(namespace)) => {
+                    // Items in blocks, e.g., methods in `impl`, do not shadow the prelude.
+                    if self.depth == 0 {
+                        self.defined.push((namespace, ident.clone()));
+                    }
+                    state = Normal;
+                }
+                (TokenTree::Ident(ident), Binding) => {
+                    if ident != "mut" {
+                        state = Normal;
+                    }
+                }
+                // field name `Struct { name: value }`
+                (TokenTree::Ident(_), Normal)
+                    if delimiter == Delimiter::Brace && !where_clause && colon => {}
+                (TokenTree::Ident(ident), Normal | Pointer)
+                    if self.kept.iter().any(|kept| same_name(kept, ident)) => {}
+                (TokenTree::Ident(ident), Normal | Pointer) => {
+                    if let Some(Macro(_, tokens)) =
+                        self.macros.iter().find(|item| same_name(&item.0, ident))
+                    {
+                        *self.used.entry(ident.unraw()).or_default() += 1;
+                        output.extend(tokens.clone());
+                        continue;
+                    }
+                    // The first segment of a path `Name::` is a type or module.
+                    let path = matches!(
+                        tokens.get(i + 1),
+                        Some(TokenTree::Punct(punct))
+                            if punct.spacing() == Spacing::Joint && punct.as_char() == ':'
+                    );
+                    let namespace = if ty || path {
+                        Namespace::Type
+                    } else {
+                        Namespace::Value
+                    };
+                    if let Some(path) = self
+                        .namespaced
+                        .get(&(namespace, ident.unraw()))
+                        .or_else(|| self.uses.get(&ident.unraw()))
+                    {
+                        *self.used.entry(ident.unraw()).or_default() += 1;
+                        // Errors in the path point to the name instead of the import.
+                        output.extend(path.to_tokens_spanned(ident.span()));
+                        continue;
+                    }
+                }
+                (TokenTree::Ident(ident), Pound) => {
+                    self.interpolated.push(ident.clone());
+                    state = Normal;
+                }
+                // first colon
+                (TokenTree::Punct(punct), _)
+                    if punct.spacing() == Spacing::Joint && punct.as_char() == ':' =>
+                {
+                    state = Path;
+                }
+                // second colon
+                (TokenTree::Punct(punct), _) if punct.as_char() == ':' => (),
+                // repetition `#(...)*` and `#(...),*`
+                (TokenTree::Punct(punct), _)
+                    if punct.as_char() == '*' && ends_with_repetition(&tokens[..i]) =>
+                {
+                    state = Normal;
+                }
+                // raw pointer `*const T`
+                (TokenTree::Punct(punct), _) if punct.as_char() == '*' => {
+                    state = Pointer;
+                }
+                // range `..` and `..=`
+                (TokenTree::Punct(punct), Range) if punct.as_char() == '.' => {
+                    state = Normal;
+                }
+                (TokenTree::Punct(punct), _)
+                    if punct.spacing() == Spacing::Joint && punct.as_char() == '.' =>
+                {
+                    state = Range;
+                }
+                // method call or field access `.ident`
+                (TokenTree::Punct(punct), _) if punct.as_char() == '.' => {
+                    state = Dot;
+                }
+                // lifetime `'static`
+                (TokenTree::Punct(punct), _) if punct.as_char() == '\'' => {
+                    state = Lifetime;
+                }
+                // quote var `#ident`
+                (TokenTree::Punct(punct), _) if punct.as_char() == '#' => {
+                    state = Pound;
+                }
+                (TokenTree::Group(group), _) => {
+                    state = Normal;
+                    // `$expr` of `macro_rules!` is passed as `None`-delimited group.
+                    let nested = group.delimiter() != Delimiter::None;
+                    self.depth += usize::from(nested);
+                    let tokens = self.replace_in_group(
+                        group.stream(),
+                        group.delimiter(),
+                        ty && group.delimiter() != Delimiter::Brace,
+                    );
+                    self.depth -= usize::from(nested);
+                    if group.delimiter() == Delimiter::None {
+                        output.extend(tokens);
+                    } else {
+                        let mut replaced = Group::new(group.delimiter(), tokens);
+                        replaced.set_span(group.span());
+                        output.extend([TokenTree::Group(replaced)]);
+                    }
+                    continue;
+                }
+                _ => {
+                    state = Normal;
+                }
+            };
+            output.extend([token.clone()]);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn replaced_span() {
+        let input: TokenStream = "# use a::b::Name;\nlet x = Name;".parse().unwrap();
+        let (_, tokens) = syn::parse2::<QuoteUse>(input).unwrap().expand().unwrap();
+        let tokens: Vec<_> = tokens.into_iter().collect();
+        assert_eq!(
+            TokenStream::from_iter(tokens.clone()).to_string(),
+            "let x = :: a :: b :: Name ;"
+        );
+        // `::a::b::Name` points to `Name` in `let x = Name;`.
+        for token in &tokens[3..tokens.len() - 1] {
+            let start = token.span().start();
+            assert_eq!((start.line, start.column), (2, 8), "{token}");
+        }
+    }
+
+    #[test]
+    fn shadowed_prelude() {
+        let warnings = |input: &str| {
+            let input: TokenStream = input.parse().unwrap();
+            let (items, _) = syn::parse2::<QuoteUse>(input).unwrap().expand().unwrap();
+            items.to_string()
+        };
+        assert!(warnings("struct Result; fn drop() {}").contains("`Result` shadows the prelude"));
+        assert!(warnings("fn drop() {}").contains("`drop` shadows the prelude"));
+        // Methods are not top-level, and `test` and `line` are macros.
+        assert_eq!(warnings("impl Drop for X { fn drop(&mut self) {} }"), "");
+        assert_eq!(warnings("mod test {} fn line() {}"), "");
+        // `Some` is a value, `Option` a type.
+        assert_eq!(warnings("type Some = u8; fn Option() {}"), "");
+    }
+
+    #[test]
+    fn include_error_span() {
+        let input: TokenStream = "# use include(\"missing.rs\");".parse().unwrap();
+        let Err(err) = syn::parse2::<QuoteUse>(input) else {
+            panic!("expected missing file to error");
+        };
+        assert!(err.to_string().starts_with("failed to read"), "{err}");
+        // The error points to `"missing.rs"`.
+        let (start, end) = (err.span().start(), err.span().end());
+        assert_eq!((start.column, end.column), (14, 26));
+    }
+
+    #[test]
+    fn group_span() {
+        let input: TokenStream = "# use a::Name;\nf( [Name] )".parse().unwrap();
+        let (_, tokens) = syn::parse2::<QuoteUse>(input).unwrap().expand().unwrap();
+        let tokens: Vec<_> = tokens.into_iter().collect();
+        let TokenTree::Group(parenthesized) = &tokens[1] else {
+            panic!("expected group, found {}", tokens[1]);
+        };
+        let (start, end) = (parenthesized.span().start(), parenthesized.span().end());
+        assert_eq!((start.line, start.column, end.column), (2, 1, 11));
+        let Some(TokenTree::Group(bracketed)) = parenthesized.stream().into_iter().next() else {
+            panic!("expected group in {parenthesized}");
+        };
+        assert_eq!(bracketed.to_string(), "[:: a :: Name]");
+        let (start, end) = (bracketed.span().start(), bracketed.span().end());
+        assert_eq!((start.line, start.column, end.column), (2, 3, 9));
+        let (open, close) = (
+            bracketed.span_open().start(),
+            bracketed.span_close().start(),
+        );
+        assert_eq!((open.column, close.column), (3, 8));
+    }
+}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use proc_macro2::{Ident, Span};
+use syn::Token;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+
+use crate::use_parser::UseItem;
+use crate::{Directives, Edition, Prelude, Use};
+
+/// The prelude selected by `directives`.
+pub(crate) fn prelude(directives: &Directives) -> Box<dyn Iterator<Item = Use>> {
+    if let Some(preludes) = &directives.preludes {
+        let mut preludes = preludes.clone();
+        // `core` takes precedence over `std`, and `std` over `alloc`, independent
+        // of the order.
+        preludes.sort();
+        preludes.dedup();
+        return Box::new(preludes.into_iter().flat_map(|prelude| {
+            parse_prelude(match prelude {
+                Prelude::Core => include_str!("prelude/core.rs"),
+                Prelude::Std => include_str!("prelude/std.rs"),
+                Prelude::Alloc => include_str!("prelude/alloc.rs"),
+                Prelude::Rust2021 => include_str!("prelude/2021.rs"),
+                Prelude::Rust2024 => include_str!("prelude/2024.rs"),
+            })
+        }));
+    }
+    let Directives {
+        no_std,
+        no_2021_prelude,
+        edition,
+        ..
+    } = *directives;
+    let mut prelude: Box<dyn Iterator<Item = Use>> =
+        Box::new(parse_prelude(include_str!("prelude/core.rs")));
+    if !no_std {
+        prelude = Box::new(prelude.chain(parse_prelude(include_str!("prelude/std.rs"))));
+    }
+    // The edition preludes are rooted at `core`, i.e., apply with `no_std` as well.
+    if edition >= Edition::Edition2021 && !no_2021_prelude {
+        prelude = Box::new(prelude.chain(parse_prelude(include_str!("prelude/2021.rs"))));
+    }
+    if edition >= Edition::Edition2024 {
+        prelude = Box::new(prelude.chain(parse_prelude(include_str!("prelude/2024.rs"))));
+    }
+    prelude
+}
+
+type Parsed = Rc<[(Vec<String>, String)]>;
+
+thread_local! {
+    /// Parsed prelude files, as names, because idents are only valid for a
+    /// single proc-macro invocation.
+    static PARSED: RefCell<HashMap<&'static str, Parsed>> = RefCell::default();
+}
+
+fn parse_prelude(file: &'static str) -> impl Iterator<Item = Use> {
+    let parsed = PARSED.with_borrow_mut(|parsed| {
+        parsed
+            .entry(file)
+            .or_insert_with(|| {
+                Punctuated::<UseItem, Token![;]>::parse_terminated
+                    .parse_str(file)
+                    .expect("prelude should be valid")
+                    .into_iter()
+                    .flat_map(|u| u.0.into_iter())
+                    .map(|Use(path, name)| (path.segments(), name.to_string()))
+                    .collect()
+            })
+            .clone()
+    });
+    (0..parsed.len()).map(move |i| {
+        let (path, name) = &parsed[i];
+        let ident = |name: &str| Ident::new(name, Span::call_site());
+        Use::with_alias(path.iter().map(|segment| ident(segment)), ident(name))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use quote::ToTokens;
+
+    use super::*;
+
+    #[test]
+    fn cached() {
+        let file = include_str!("prelude/core.rs");
+        let uncached: Vec<_> = Punctuated::<UseItem, Token![;]>::parse_terminated
+            .parse_str(file)
+            .unwrap()
+            .into_iter()
+            .flat_map(|u| u.0)
+            .map(|Use(path, name)| (path.into_token_stream().to_string(), name))
+            .collect();
+        for _ in 0..2 {
+            let cached: Vec<_> = parse_prelude(file)
+                .map(|Use(path, name)| (path.into_token_stream().to_string(), name))
+                .collect();
+            assert_eq!(cached, uncached);
+        }
+    }
+
+    #[test]
+    fn unique() {
+        for file in [
+            include_str!("prelude/core.rs"),
+            include_str!("prelude/std.rs"),
+            include_str!("prelude/2021.rs"),
+            include_str!("prelude/2024.rs"),
+            include_str!("prelude/alloc.rs"),
+        ] {
+            let mut names = HashSet::new();
+            for Use(_, ident) in parse_prelude(file) {
+                assert!(names.insert(ident.to_string()), "duplicate `{ident}`");
+            }
+        }
+
+        for no_std in [false, true] {
+            let directives = Directives {
+                no_std,
+                edition: Edition::Edition2024,
+                ..Directives::default()
+            };
+            let mut names = HashSet::new();
+            for Use(_, ident) in prelude(&directives) {
+                assert!(names.insert(ident.to_string()), "duplicate `{ident}`");
+            }
+        }
+    }
+}
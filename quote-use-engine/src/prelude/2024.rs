@@ -0,0 +1 @@
+use core::prelude::rust_2024::{Future, IntoFuture};
@@ -0,0 +1,3 @@
+use alloc::{
+    borrow::ToOwned, boxed::Box, string::{String, ToString}, vec::Vec
+};
@@ -0,0 +1,499 @@
+use proc_macro2::{Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use quote::{ToTokens, TokenStreamExt};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::token::Brace;
+use syn::{Error, Result, Token, Visibility, braced};
+
+#[derive(Debug, Clone)]
+pub enum IdentOrPounded {
+    Ident(Ident),
+    Pounded(Token![#], TokenTree),
+}
+
+impl IdentOrPounded {
+    fn is_self(&self) -> bool {
+        if let Self::Ident(ident) = self {
+            ident == "self"
+        } else {
+            false
+        }
+    }
+
+    fn is_ident(&self) -> bool {
+        matches!(self, Self::Ident(_))
+    }
+
+    /// Whether this is a path root relative to the current module, e.g.,
+    /// `super`.
+    fn is_relative(&self) -> bool {
+        matches!(
+            self,
+            Self::Ident(ident)
+                if ["crate", "self", "super", "Self"].iter().any(|root| ident == root)
+        )
+    }
+}
+
+impl ToTokens for IdentOrPounded {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            IdentOrPounded::Ident(ident) => ident.to_tokens(tokens),
+            IdentOrPounded::Pounded(pound, tt) => {
+                pound.to_tokens(tokens);
+                tt.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+impl Parse for IdentOrPounded {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ident::parse_any(input)
+            .map(Self::Ident)
+            .or_else(|_| Ok(Self::Pounded(input.parse()?, input.parse()?)))
+    }
+}
+
+/// Path segments, and the root to use instead of `::`, an empty root omitting
+/// the leading `::`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Path(Vec<IdentOrPounded>, Option<TokenStream>);
+
+impl Path {
+    pub(crate) fn set_root(&mut self, root: TokenStream) {
+        self.1 = Some(root);
+    }
+
+    /// Omits the leading `::`, unless another root is set.
+    pub(crate) fn strip_leading_colons(&mut self) {
+        self.1.get_or_insert_with(TokenStream::new);
+    }
+
+    /// Replaces the first segment with `root`, if it is `name`.
+    pub(crate) fn replace_first(&mut self, name: &str, root: TokenStream) {
+        if matches!(self.0.first(), Some(IdentOrPounded::Ident(first)) if first == name) {
+            self.0.remove(0);
+            self.set_root(root);
+        }
+    }
+
+    /// Renames the first segment to `to`, if it is `name`.
+    pub(crate) fn rename_first(&mut self, name: &str, to: &str) {
+        if let Some(IdentOrPounded::Ident(first)) = self.0.first_mut() {
+            if first == name {
+                *first = Ident::new(to, first.span());
+            }
+        }
+    }
+
+    /// Whether the segments are `path`, e.g., `"std::io::Write"`.
+    pub(crate) fn is(&self, path: &str) -> bool {
+        self.0.len() == path.split("::").count()
+            && self.0.iter().zip(path.split("::")).all(
+                |(segment, name)| matches!(segment, IdentOrPounded::Ident(ident) if ident == name),
+            )
+    }
+
+    /// The segments as strings, e.g., `["std", "io", "Write"]`.
+    pub(crate) fn segments(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|segment| segment.to_token_stream().to_string())
+            .collect()
+    }
+
+    fn push(&mut self, value: IdentOrPounded) {
+        self.0.push(value);
+    }
+
+    /// Removes a trailing `self`, e.g., in `a::{self}`.
+    fn pop_self(&mut self) -> Result<()> {
+        if self.0.last().is_some_and(IdentOrPounded::is_self) {
+            if let [only] = &self.0[..] {
+                return Err(Error::new_spanned(
+                    only,
+                    "`self` can only be imported in a group, e.g., `a::{self}`",
+                ));
+            }
+            self.pop();
+        }
+        Ok(())
+    }
+
+    fn get_ident(&self) -> Result<&Ident> {
+        match self.0.last().expect("path should contain a segment") {
+            IdentOrPounded::Ident(ident) => Ok(ident),
+            segment @ IdentOrPounded::Pounded(_, var) => Err(Error::new_spanned(
+                segment,
+                format_args!(
+                    "interpolated `#{var}` cannot be used as an import name; add `as <ident>`"
+                ),
+            )),
+        }
+    }
+
+    fn pop(&mut self) {
+        self.0
+            .pop()
+            .expect("path should contain at least one segment");
+    }
+
+    /// The path with its idents and `::` spanned at `span`, e.g., the span of
+    /// the replaced name.
+    ///
+    /// Interpolated segments and the root keep their spans, as these determine
+    /// which variables they refer to.
+    pub(crate) fn to_tokens_spanned(&self, span: Span) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        self.write_tokens(&mut tokens, Some(span));
+        tokens
+    }
+
+    fn write_tokens(&self, tokens: &mut TokenStream, span: Option<Span>) {
+        let colons = || {
+            let mut colons = [
+                Punct::new(':', Spacing::Joint),
+                Punct::new(':', Spacing::Alone),
+            ];
+            if let Some(span) = span {
+                colons.iter_mut().for_each(|colon| colon.set_span(span));
+            }
+            colons
+        };
+        let Some(first) = self.0.first() else {
+            // The root itself, e.g., `$crate`.
+            self.1.to_tokens(tokens);
+            return;
+        };
+        match &self.1 {
+            _ if !first.is_ident() || first.is_relative() => {}
+            Some(root) if root.is_empty() => {}
+            Some(root) => {
+                root.to_tokens(tokens);
+                tokens.append_all(colons());
+            }
+            None => tokens.append_all(colons()),
+        }
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                tokens.append_all(colons());
+            }
+            match (segment, span) {
+                (IdentOrPounded::Ident(ident), Some(span)) => {
+                    let mut ident = ident.clone();
+                    ident.set_span(span);
+                    ident.to_tokens(tokens);
+                }
+                _ => segment.to_tokens(tokens),
+            }
+        }
+    }
+}
+
+impl ToTokens for Path {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.write_tokens(tokens, None);
+    }
+}
+
+/// An import of a path as a name.
+#[derive(Clone, Debug)]
+pub struct Use(pub(crate) Path, pub(crate) Ident);
+
+impl Use {
+    /// The name replaced by the path, i.e., `b` for `use ::a::b;`.
+    pub fn name(&self) -> &Ident {
+        &self.1
+    }
+
+    /// The path the name is replaced with, e.g., `::a::b`.
+    pub fn path(&self) -> TokenStream {
+        self.0.to_token_stream()
+    }
+
+    /// Imports the last segment of `path`, i.e., `use ::a::b;`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `path` is empty.
+    pub fn new(path: impl IntoIterator<Item = Ident>) -> Self {
+        let path: Vec<_> = path.into_iter().collect();
+        let name = path.last().expect("path should not be empty").clone();
+        Self::with_alias(path, name)
+    }
+
+    /// Imports `path` as `alias`, i.e., `use ::a::b as alias;`.
+    pub fn with_alias(path: impl IntoIterator<Item = Ident>, alias: Ident) -> Self {
+        Self(
+            Path(path.into_iter().map(IdentOrPounded::Ident).collect(), None),
+            alias,
+        )
+    }
+
+    /// Parses a `use` item, e.g., `use a::{b, c as d};`, into its imports.
+    ///
+    /// # Errors
+    ///
+    /// Errors when `input` is not a `use` item, or uses a glob import.
+    pub fn parse_item(input: ParseStream) -> Result<Vec<Self>> {
+        UseItem::parse(input).map(|item| item.0)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UseItem(pub Vec<Use>);
+
+const MISSING_SEMICOLON: &str = "use statement must end with `;`";
+
+/// Errors at `span` if a top level use tree, i.e., not `inner`, is not followed
+/// by `;`.
+fn expect_end(input: ParseStream, inner: bool, span: Span) -> Result<()> {
+    if inner || input.peek(Token![;]) {
+        Ok(())
+    } else {
+        Err(Error::new(span, MISSING_SEMICOLON))
+    }
+}
+
+// INPUTS:
+// a::b::{a::{}, b}
+fn parse_use_segment(
+    parent: &Path,
+    input: ParseStream,
+    output: &mut Vec<Use>,
+    inner: bool,
+) -> Result<()> {
+    let mut path = parent.clone();
+    loop {
+        let la = input.lookahead1();
+        if la.peek(Ident::peek_any) || la.peek(Token![#]) {
+            path.push(input.parse()?);
+            let la = input.lookahead1();
+            if inner && (la.peek(Token![,]) || input.is_empty()) || !inner && la.peek(Token![;]) {
+                path.pop_self()?;
+                output.push(Use(path.clone(), path.get_ident()?.clone()));
+                break;
+            } else if la.peek(Token![as]) {
+                input.parse::<Token![as]>()?;
+                // `as _` only brings a trait into scope, which is not possible without
+                // emitting `use`, i.e., there is no name to replace.
+                if let Some(underscore) = input.parse::<Option<Token![_]>>()? {
+                    expect_end(input, inner, underscore.span)?;
+                    break;
+                }
+                let alias: Ident = input.parse()?;
+                expect_end(input, inner, alias.span())?;
+                path.pop_self()?;
+                output.push(Use(path, alias));
+                break;
+            } else if la.peek(Token![::]) {
+                input.parse::<Token![::]>()?;
+                continue;
+            } else if !inner {
+                return Err(Error::new_spanned(path.0.last(), MISSING_SEMICOLON));
+            } else {
+                return Err(la.error());
+            }
+        } else if input.peek(Token![*]) {
+            return Err(Error::new_spanned(
+                input.parse::<Token![*]>()?,
+                "glob imports are not supported, as the imported names are unknown; import the \
+                 used names explicitly instead",
+            ));
+        } else if la.peek(Brace) {
+            // A group
+            let content;
+            let brace = braced!(content in input);
+            // `use a::{};` imports nothing
+            while !content.is_empty() {
+                // `use {::a, b};`
+                if path.0.is_empty() {
+                    Option::<Token![::]>::parse(&content)?;
+                }
+                parse_use_segment(&path, &content, output, true)?;
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+            let la = input.lookahead1();
+            if inner && (input.is_empty() || la.peek(Token![,])) || !inner && la.peek(Token![;]) {
+                break;
+            } else if !inner {
+                return Err(Error::new(brace.span.close(), MISSING_SEMICOLON));
+            } else {
+                return Err(la.error());
+            }
+        } else {
+            return Err(la.error());
+        }
+    }
+    Ok(())
+}
+
+impl UseItem {
+    /// Parses the tree of a `use` item without `use` and `;`, e.g.,
+    /// `a::{b, c}`, it needs to be followed by `;`.
+    pub fn parse_tree(input: ParseStream) -> Result<Self> {
+        let mut output = Vec::new();
+        Option::<Token![::]>::parse(input)?;
+
+        parse_use_segment(&Default::default(), input, &mut output, false)?;
+
+        Ok(Self(output))
+    }
+}
+
+impl UseItem {
+    /// Parses `extern crate a as b;`, importing the crate `::a` as `b`.
+    fn parse_extern_crate(input: ParseStream) -> Result<Self> {
+        <Token![extern]>::parse(input)?;
+        <Token![crate]>::parse(input)?;
+        let name = Ident::parse_any(input)?;
+        let alias = if input.parse::<Option<Token![as]>>()?.is_some() {
+            // `as _` only links the crate, there is no name to replace.
+            if input.parse::<Option<Token![_]>>()?.is_some() {
+                <Token![;]>::parse(input)?;
+                return Ok(Self::default());
+            }
+            input.parse()?
+        } else if name == "self" {
+            return Err(Error::new(
+                name.span(),
+                "`extern crate self;` requires an alias, e.g., `extern crate self as name;`",
+            ));
+        } else {
+            name.clone()
+        };
+        <Token![;]>::parse(input)?;
+        // `extern crate self as a;` refers to the current crate.
+        let path = if name == "self" {
+            Path(
+                vec![IdentOrPounded::Ident(Ident::new("crate", name.span()))],
+                None,
+            )
+        } else {
+            Path(vec![IdentOrPounded::Ident(name)], None)
+        };
+        Ok(Self(vec![Use(path, alias)]))
+    }
+}
+
+impl Parse for UseItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+        // Visibility is meaningless for replacing, but allows copying `pub use`.
+        Visibility::parse(input)?;
+        if input.peek(Token![extern]) {
+            return Self::parse_extern_crate(input);
+        }
+        <Token![use]>::parse(input)?;
+        let output = Self::parse_tree(input)?;
+        <Token![;]>::parse(input)?;
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use quote::ToTokens;
+    use syn::parse::Parser;
+    use syn::parse_str;
+
+    use super::*;
+
+    macro_rules! assert_use_item {
+        ($use:literal $(, $path:literal as $ident:ident)* $(,)*) => {
+            let UseItem(uses) = parse_str($use).unwrap();
+            let mut uses = uses.into_iter();
+            $(
+                let Use(path, ident) = uses.next().unwrap();
+                assert_eq!(path.into_token_stream().to_string().replace(' ', ""), $path);
+                assert_eq!(ident, stringify!($ident));
+            )*
+            assert!(uses.next().is_none());
+        };
+    }
+
+    #[test]
+    fn use_item() {
+        assert_use_item!("use ::a::b;", "::a::b" as b);
+        assert_use_item!("use super::module as m;", "super::module" as m);
+        assert_use_item!("use self::module::Name;", "self::module::Name" as Name);
+        assert_use_item!("use Self::Variant;", "Self::Variant" as Variant);
+        assert_use_item!("use a::{Trait as _, b};", "::a::b" as b);
+        assert_use_item!(
+            "use crate::a::{b, c};",
+            "crate::a::b" as b,
+            "crate::a::c" as c
+        );
+        assert_use_item!(
+            "use a::{c, self, b};",
+            "::a::c" as c,
+            "::a" as a,
+            "::a::b" as b
+        );
+        assert_use_item!("use a::{self as c, b as a};", "::a" as c, "::a::b" as a);
+        assert_use_item!(
+            "use a::{b::{a, b}, c};",
+            "::a::b::a" as a,
+            "::a::b::b" as b,
+            "::a::c" as c
+        );
+        assert_use_item!("use a::r#type;", "::a::r#type" as r#type);
+        assert_use_item!("use r#match::{r#fn as f};", "::r#match::r#fn" as f);
+        assert_use_item!("use #var::a;", "#var::a" as a);
+        assert_use_item!("use ::a::#var::a;", "::a::#var::a" as a);
+        assert_use_item!("use ::a::#var as a;", "::a::#var" as a);
+        assert_use_item!("pub use a::b;", "::a::b" as b);
+        assert_use_item!("pub(crate) use a::{b, c};", "::a::b" as b, "::a::c" as c);
+        assert_use_item!("pub(super) use a::b as c;", "::a::b" as c);
+        assert_use_item!("pub(in crate::a) use b::c;", "::b::c" as c);
+        assert_use_item!(
+            "use {std::fs::read, std::fs::write as w,};",
+            "::std::fs::read" as read,
+            "::std::fs::write" as w
+        );
+        assert_use_item!(
+            "use {a::{b, c as d}, e};",
+            "::a::b" as b,
+            "::a::c" as d,
+            "::e" as e
+        );
+        assert_use_item!("extern crate serde;", "::serde" as serde);
+        assert_use_item!("extern crate serde as s;", "::serde" as s);
+        assert_use_item!("pub extern crate serde as s;", "::serde" as s);
+        assert_use_item!("extern crate self as foo;", "crate" as foo);
+        assert_use_item!("extern crate serde as _;");
+        assert_use_item!("use foo::{};");
+        assert_use_item!("use foo::{a,};", "::foo::a" as a);
+        assert_use_item!("use foo::{a, {}, b::{}};", "::foo::a" as a);
+    }
+
+    macro_rules! assert_error {
+        ($use:literal) => {
+            UseItem::parse.parse_str($use).unwrap_err();
+        };
+    }
+
+    #[test]
+    fn error() {
+        assert_error!("use ::a::#b;");
+        assert_error!("use a::{self, *};");
+        assert_error!("use smth::prelude::*;");
+        assert_error!("use {self};");
+        assert_error!("use self;");
+        assert_error!("use {a, {self}};");
+        assert_error!("use a::b c;");
+        assert_error!("use a::b as c d;");
+        assert_error!("extern crate self;");
+        assert_error!("extern crate a::b;");
+        assert_error!("extern crate a as b");
+        assert_error!("use a::{,};");
+        assert_error!("use a::{b,,};");
+    }
+}
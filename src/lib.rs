@@ -27,6 +27,116 @@
 //! # ;
 //! ```
 //!
+//! Imports are only applied to the first segment of a path, i.e., `ho::Name`
+//! is replaced when `ho` is imported, while `other::Name` is left untouched
+//! even when `Name` is imported. The first segment always resolves to the
+//! import, even when the alias shadows a segment of its own path:
+//!
+//! ```
+//! # use quote::quote;
+//! # use quote_use::quote_use;
+//! let tokens = quote_use! {
+//!     ## use a::b as a;
+//!
+//!     a::c
+//! };
+//! assert_eq!(tokens.to_string(), quote!(::a::b::c).to_string());
+//! ```
+//!
+//! Raw identifiers are the same name as their non-raw spelling, i.e., `# use
+//! a::r#Name;` replaces both `r#Name` and `Name`.
+//!
+//! Glob imports, e.g., `# use smth::prelude::*;`, are not supported, as the
+//! imported names are unknown, i.e., it is unknown which names to replace.
+//! Neither are attributes on imports, e.g., `# #[cfg(..)] use a::B;`, as a
+//! `#[cfg]` cannot be evaluated when replacing.
+//!
+//! As imports are replaced instead of emitted, traits are not brought into
+//! scope. Importing a trait commonly used for its methods, e.g.,
+//! `std::io::Write` for `write!`, without using its name produces a warning.
+//! For the same reason, `# use Trait as _;` is accepted, but has no effect.
+//! Visibilities, e.g., `# pub(crate) use a::B;`, are accepted and ignored as
+//! well, allowing to copy imports from regular code.
+//!
+//! Multiple imports can be combined in a group, i.e., `# use {std::fs::read,
+//! std::io::Write};`.
+//!
+//! Crates can also be aliased with `# extern crate serde as s;`, replacing `s`
+//! with `::serde`. `# extern crate self as name;` replaces `name` with `crate`.
+//!
+//! ## Relative imports
+//!
+//! Imports are resolved from the crate root, i.e., `::smth::ho::Name`. For
+//! code that is placed inside a nested module, `# use relative_to(path);`
+//! replaces the leading `::` of all imports (not the prelude) with `path::`,
+//! e.g., `# use relative_to(super::super);` resolves `# use smth::ho::Name;`
+//! to `super::super::smth::ho::Name`. Imports starting with `crate`, `self`,
+//! `super` or `Self` are kept as is.
+//!
+//! When generating an exported `macro_rules!`, paths into the crate
+//! containing the macro need to start with `$crate`. With `# use
+//! dollar_crate(my_crate);`, imports starting with `my_crate` are rooted at
+//! `$crate` instead, e.g., `# use my_crate::Name;` resolves to `$crate::Name`.
+//! To root all imports at `$crate`, use `# use relative_to($crate);`. Imports
+//! starting with an interpolated variable, e.g., `# use #root::Name;`, are
+//! never rooted.
+//!
+//! ## Including imports
+//!
+//! Imports shared between many invocations can be put in a file containing
+//! only `use` statements, that is included with `# use include("file.rs");`.
+//! The path is relative to the invoking source file, or to the crate root if
+//! the source file is unknown.
+//!
+//! ## Import groups
+//!
+//! Imports used multiple times can be defined as a group with `# use group
+//! Name = {a::B, c::D};` and then imported with `# use Name;`.
+//!
+//! ## Namespaces
+//!
+//! Types and values can share a name, e.g., a struct `Foo` and a function
+//! `Foo`. With `# use type a::Foo;` and `# use value b::Foo;`, `Foo` is
+//! replaced depending on its position:
+//!
+//! ```
+//! # use quote::quote;
+//! # use quote_use::quote_use;
+//! let tokens = quote_use! {
+//!     ## use type a::Foo;
+//!     ## use value b::Foo;
+//!
+//!     let foo: Foo = Foo(1);
+//! };
+//! assert_eq!(
+//!     tokens.to_string(),
+//!     quote!(let foo: ::a::Foo = ::b::Foo(1);).to_string()
+//! );
+//! ```
+//!
+//! As the tokens are not parsed, the position is a heuristic: names after `:`,
+//! `->`, `as`, `impl` and `dyn`, inside their generics, and at the start of a
+//! path `Foo::` are types, all others are values.
+//!
+//! ## Macros
+//!
+//! For replacements that are not paths, `# macro NAME = tokens;` replaces every
+//! `NAME` with `tokens`, which are inserted verbatim:
+//!
+//! ```
+//! # use quote::quote;
+//! # use quote_use::quote_use;
+//! let tokens = quote_use! {
+//!     ## macro DB = crate::db::Database::connection();
+//!
+//!     DB.query()
+//! };
+//! assert_eq!(
+//!     tokens.to_string(),
+//!     quote!(crate::db::Database::connection().query()).to_string()
+//! );
+//! ```
+//!
 //! ## Prelude
 //!
 //! This also allows using contents of the rust prelude directly:
@@ -51,6 +161,16 @@
 //! }
 //! # ;
 //! ```
+//! Defining an item with the name of a prelude item, e.g., `struct Result;`,
+//! produces a warning, as other uses of `Result` still refer to the prelude.
+//!
+//! To keep a name as is, e.g., for a variable named like a prelude item, use
+//! `# use keep Name;`.
+//!
+//! To prevent accidentally overriding the prelude, `# use lock_prelude;` makes
+//! the prelude take precedence, importing a prelude name then produces a
+//! warning.
+//!
 //! ### Different preludes
 //!
 //! By default [`quote_use!`] uses the [core prelude](core::prelude), [std
@@ -58,22 +178,295 @@
 //! Preferring `core` where available.
 //!
 //! All preludes can be disabled by adding `# use no_prelude;` at the top of the
-//! macro input. The `std` prelude can be disabled with `# use no_std_prelude;`.
+//! macro input. The `std` prelude can be disabled with `# use no_std;`, keeping
+//! the edition preludes, as they are part of `core`. The 2021 edition prelude
+//! can be disabled with `# use no_2021_prelude;` or
+//! [`quote_use_no_2021_prelude!`], e.g., to not collide with own `TryFrom`
+//! traits. `# use prefer_std;` uses the `std` paths for names that are part of
+//! `core` as well, e.g., `::std::prelude::v1::Some`.
+//!
+//! The edition prelude is selected with `# use edition(2024);`, i.e., `2015`
+//! and `2018` use no edition prelude, and `2024` adds `Future` and
+//! `IntoFuture`.
+//!
+//! Alternatively, `# use prelude(core, std);` selects the preludes explicitly,
+//! from `core`, `std`, `alloc`, `rust_2021` and `rust_2024`, or none with `#
+//! use prelude(none);`. E.g., `# use prelude(core, alloc, rust_2021);` uses the
+//! `alloc` prelude instead of the `std` prelude, replacing `String` with
+//! `::alloc::string::String`, which requires `extern crate alloc;`.
+//!
+//! ### Custom prelude
+//!
+//! `# use prelude a::{B, C};` adds imports to the prelude, i.e., they take
+//! precedence over the default preludes, but can still be overridden by other
+//! imports. This allows wrapping [`quote_use!`] with the imports used across a
+//! crate:
+//!
+//! ```
+//! # use quote::quote;
+//! macro_rules! my_quote {
+//!     ($($tokens:tt)*) => {
+//!         quote_use::quote_use! {
+//!             ## use prelude my_crate::{Error, Result};
+//!             $($tokens)*
+//!         }
+//!     };
+//! }
+//!
+//! let tokens = my_quote! {
+//!     ## use std::fmt::Error;
+//!
+//!     Result<(), Error>
+//! };
+//! assert_eq!(
+//!     tokens.to_string(),
+//!     quote!(::my_crate::Result<(), ::std::fmt::Error>).to_string()
+//! );
+//! ```
+//!
+//! ## Directives
+//!
+//! Imports of a single name, like `# use no_prelude;`, are directives
+//! configuring the macro. Unknown names are treated as imports of a crate. To
+//! make this explicit, prefix the crate with `::`, i.e., `# use ::serde;`.
+//! After `# use strict;`, unknown directives are an error. With the `syn`
+//! feature, all directives are listed in [`DIRECTIVES`].
+//!
+//! After `# use strict;`, importing a single uppercase letter, e.g., `# use
+//! a::T;`, is an error as well, as it also replaces generic parameters named
+//! `T`. Without it, single letters can be imported, e.g., constants.
+//!
+//! To find imports that never match, e.g., due to a typo, `# use warn_unused;`
+//! produces a warning for each import that is never used.
+//!
+//! Paths are emitted with a leading `::`, e.g., `::smth::ho::Name`. When
+//! emitting into a context where this fails, e.g., edition 2015 code, `# use
+//! no_leading_colon;` omits it, producing `smth::ho::Name`. This also applies
+//! to the prelude.
+//!
+//! Imports are never applied to interpolated variables, as their tokens are
+//! only known at runtime. With `# use strict_interpolation;` it is an error
+//! when an imported name is only used as an interpolated variable, i.e.,
+//! `#Name`.
 //!
 //! ## Other quote macros
 //!
 //! There are also variants for other quote macros from [syn] and [mod@quote]:
 //!
 //! - [`quote_use!`] and [`quote_spanned_use!`] as replacement for [`quote!`]
-//!   and
-//! [`quote_spanned!`](quote::quote_spanned!) respectively
+//!   and [`quote_spanned!`](quote::quote_spanned!) respectively
 //! - [`parse_quote_use!`] and [`parse_quote_spanned_use!`] for
-//!   [`parse_quote!`](syn::parse_quote!)
-//! and [`parse_quote_spanned!`](syn::parse_quote_spanned!)
+//!   [`parse_quote!`](syn::parse_quote!) and
+//!   [`parse_quote_spanned!`](syn::parse_quote_spanned!)
+//! - [`quote_use_append!`] to extend an existing `TokenStream`, i.e.,
+//!   `quote_use_append!(&mut tokens; ...)`
+//!
+//! ## Debugging
+//!
+//! With the `passthrough` feature, all macros ignore the `# use` statements and
+//! behave exactly like the macros they replace, e.g., [`quote_use!`] like
+//! [`quote!`]. This helps to tell apart bugs in `quote-use` from bugs in the
+//! macro using it.
+//!
+//! With the `debug` feature, `quote_use_debug!` behaves like [`quote_use!`],
+//! but lists the applied imports, including the prelude, in a warning, e.g.,
+//! ``applied imports: `Name` -> `::a::Name`, `Some` ->
+//! `::core::prelude::v1::Some` ``.
+//!
+//! ## Runtime
+//!
+//! With the `syn` feature, [`rewrite`] applies imports to a `TokenStream` at
+//! runtime, e.g., to tokens produced by another macro. [`rewrite_expr`] and
+//! [`rewrite_type`] additionally parse the result, [`rewrite_normalized`]
+//! returns it as a string with normalized spacing, and [`substitution_stats`]
+//! counts the replacements per import:
+//!
+//! ```
+//! # #[cfg(feature = "syn")] {
+//! # use proc_macro2::TokenStream;
+//! let input: TokenStream = "
+//!     ## use std::collections::HashMap;
+//!
+//!     HashMap::<String, Option<u8>>::new()
+//! "
+//! .parse()
+//! .unwrap();
+//! let expr: syn::Expr = quote_use::rewrite_expr(input).unwrap();
+//! # }
+//! ```
+//!
+//! To construct the imports programmatically, [`Rewriter::with_directives`]
+//! takes [`Directives`] and a list of [`Use`]s instead.
+//! [`replace_uses`] only applies a list of [`Use`]s, without the prelude.
+#[cfg(feature = "syn")]
+use std::collections::HashMap;
+
+#[cfg(feature = "syn")]
+use proc_macro2::{Ident, TokenStream};
 #[cfg(doc)]
 use quote::quote;
 // Reexport
-pub use quote::{format_ident, IdentFragment, ToTokens, TokenStreamExt};
+pub use quote::{IdentFragment, ToTokens, TokenStreamExt, format_ident};
+/// Names accepted as directives, i.e., `# use <directive>;`.
+#[cfg(feature = "syn")]
+pub use quote_use_engine::DIRECTIVES;
+#[cfg(feature = "syn")]
+pub use quote_use_engine::{Directives, Edition, Prelude, Rewriter, Use};
+
+/// Describes a macro provided by this crate, see [`MACROS`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MacroVariant {
+    /// Name of the macro, e.g., `"quote_use"`.
+    pub name: &'static str,
+    /// Whether the prelude is enabled by default.
+    pub prelude: bool,
+    /// Whether the macro requires the `syn` feature.
+    pub syn: bool,
+}
+
+impl MacroVariant {
+    const fn new(name: &'static str, prelude: bool, syn: bool) -> Self {
+        Self { name, prelude, syn }
+    }
+}
+
+/// All macros provided by this crate.
+pub const MACROS: &[MacroVariant] = &[
+    MacroVariant::new("quote_use", true, false),
+    MacroVariant::new("quote_spanned_use", true, false),
+    MacroVariant::new("parse_quote_use", true, true),
+    MacroVariant::new("parse_quote_spanned_use", true, true),
+    MacroVariant::new("quote_use_append", true, false),
+    MacroVariant::new("quote_use_no_prelude", false, false),
+    MacroVariant::new("quote_spanned_use_no_prelude", false, false),
+    MacroVariant::new("parse_quote_use_no_prelude", false, true),
+    MacroVariant::new("parse_quote_spanned_use_no_prelude", false, true),
+    MacroVariant::new("quote_use_no_2021_prelude", true, false),
+    #[cfg(feature = "debug")]
+    MacroVariant::new("quote_use_debug", true, false),
+];
+
+/// Applies the `# use` statements at the start of `input` to the following
+/// tokens, like [`quote_use!`] but at runtime.
+///
+/// As `input` is not passed to [`quote!`], interpolations like `#var` are kept
+/// as is.
+///
+/// # Errors
+///
+/// Errors when the `# use` statements are invalid.
+#[cfg(feature = "syn")]
+pub fn rewrite(input: TokenStream) -> syn::Result<TokenStream> {
+    // Items only emit warnings and track included files, which only applies
+    // to the proc-macros.
+    let (_items, tokens) = syn::parse2::<quote_use_engine::QuoteUse>(input)?.expand()?;
+    Ok(tokens)
+}
+
+/// [`rewrite`] returning the result with normalized spacing, e.g., for
+/// comparing it to the output of [`quote!`] in tests.
+///
+/// The spacing only depends on the tokens, i.e., it is the same as for
+/// `quote!(...).to_string()`.
+///
+/// # Errors
+///
+/// Errors when the `# use` statements are invalid.
+#[cfg(feature = "syn")]
+pub fn rewrite_normalized(input: TokenStream) -> syn::Result<String> {
+    rewrite(input).map(|tokens| tokens.to_string())
+}
+
+/// Returns how often each import in `input` was replaced, e.g., to find paths
+/// that are repeated often.
+///
+/// # Errors
+///
+/// Errors when the `# use` statements are invalid.
+#[cfg(feature = "syn")]
+pub fn substitution_stats(input: TokenStream) -> syn::Result<HashMap<Ident, usize>> {
+    Ok(syn::parse2::<quote_use_engine::QuoteUse>(input)?.substitution_stats())
+}
+
+/// [`rewrite`] parsing the result as [`syn::Expr`].
+///
+/// # Errors
+///
+/// Errors when the `# use` statements are invalid or the result is not an
+/// expression.
+#[cfg(feature = "syn")]
+pub fn rewrite_expr(input: TokenStream) -> syn::Result<syn::Expr> {
+    syn::parse2(rewrite(input)?)
+}
+
+/// [`rewrite`] parsing the result as [`syn::Type`].
+///
+/// # Errors
+///
+/// Errors when the `# use` statements are invalid or the result is not a type.
+#[cfg(feature = "syn")]
+pub fn rewrite_type(input: TokenStream) -> syn::Result<syn::Type> {
+    syn::parse2(rewrite(input)?)
+}
+
+/// Replaces the names imported by `uses` in `tokens`, without applying the
+/// prelude, e.g., for custom macros.
+///
+/// [`Use::parse_item`] parses the imports from a `use` item.
+#[cfg(feature = "syn")]
+#[must_use]
+pub fn replace_uses(uses: &[Use], tokens: TokenStream) -> TokenStream {
+    Rewriter::with_directives(Directives::default().no_prelude(true), uses.to_vec())
+        .rewrite(tokens)
+        .expect("rewriting without directives should not fail")
+}
+
+/// Replaces the names in `body` with the paths they are imported from, e.g.,
+/// for imports only known at runtime, like [`replace_uses`].
+///
+/// ```
+/// # use quote::{format_ident, quote};
+/// let imports = ["Read", "Write"].map(|name| {
+///     let name = format_ident!("{name}");
+///     (syn::parse_quote!(std::io::#name), name)
+/// });
+/// assert_eq!(
+///     quote_use::with_imports(imports, quote!(impl Read for A {}))
+///         .unwrap()
+///         .to_string(),
+///     quote!(impl ::std::io::Read for A {}).to_string()
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Errors when a path has generic arguments, e.g., `Vec<u8>`, as imports can
+/// only name paths.
+#[cfg(feature = "syn")]
+pub fn with_imports(
+    imports: impl IntoIterator<Item = (syn::Path, Ident)>,
+    body: TokenStream,
+) -> syn::Result<TokenStream> {
+    let uses = imports
+        .into_iter()
+        .map(|(path, alias)| {
+            let segments = path
+                .segments
+                .into_iter()
+                .map(|segment| match segment.arguments {
+                    syn::PathArguments::None => Ok(segment.ident),
+                    arguments => Err(syn::Error::new_spanned(
+                        arguments,
+                        "generic arguments are not supported in imports",
+                    )),
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(Use::with_alias(segments, alias))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(replace_uses(&uses, body))
+}
 
 #[doc(hidden)]
 pub mod __private {
@@ -113,6 +506,13 @@ macro_rules! parse_quote_spanned_use {
     };
 }
 
+#[macro_export]
+macro_rules! quote_use_append {
+    ($stream:expr; $($tokens:tt)*) => {
+        ::core::iter::Extend::extend($stream, $crate::quote_use!($($tokens)*))
+    };
+}
+
 #[macro_export]
 macro_rules! quote_use_no_prelude {
     ($($tokens:tt)*) => {
@@ -142,3 +542,62 @@ macro_rules! parse_quote_spanned_use_no_prelude {
         $crate::__private::quote_use_impl!{($crate::__private::syn::parse_quote_spanned) ($span =>) (#use no_prelude; $($tokens)*)}
     };
 }
+
+// Without `syn`, the `parse_*` macros explain how to enable them, instead of
+// not being found.
+#[cfg(not(feature = "syn"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_quote_use {
+    ($($tokens:tt)*) => {
+        ::core::compile_error!("enable the `syn` feature of `quote-use` to use `parse_quote_use!`")
+    };
+}
+
+#[cfg(not(feature = "syn"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_quote_spanned_use {
+    ($($tokens:tt)*) => {
+        ::core::compile_error!(
+            "enable the `syn` feature of `quote-use` to use `parse_quote_spanned_use!`"
+        )
+    };
+}
+
+#[cfg(not(feature = "syn"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_quote_use_no_prelude {
+    ($($tokens:tt)*) => {
+        ::core::compile_error!(
+            "enable the `syn` feature of `quote-use` to use `parse_quote_use_no_prelude!`"
+        )
+    };
+}
+
+#[cfg(not(feature = "syn"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_quote_spanned_use_no_prelude {
+    ($($tokens:tt)*) => {
+        ::core::compile_error!(
+            "enable the `syn` feature of `quote-use` to use `parse_quote_spanned_use_no_prelude!`"
+        )
+    };
+}
+
+#[cfg(feature = "debug")]
+#[macro_export]
+macro_rules! quote_use_debug {
+    ($($tokens:tt)*) => {
+        $crate::__private::quote_use_impl!{($crate::__private::quote::quote) () ($($tokens)*) debug}
+    };
+}
+
+#[macro_export]
+macro_rules! quote_use_no_2021_prelude {
+    ($($tokens:tt)*) => {
+        $crate::__private::quote_use_impl!{($crate::__private::quote::quote) () (#use no_2021_prelude; $($tokens)*)}
+    };
+}
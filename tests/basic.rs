@@ -1,5 +1,7 @@
+#![cfg(not(feature = "passthrough"))]
+
 use quote::quote;
-use quote_use::{quote_use, quote_use_no_prelude};
+use quote_use::{quote_use, quote_use_append, quote_use_no_2021_prelude, quote_use_no_prelude};
 
 #[test]
 fn r#use() {
@@ -47,6 +49,24 @@ fn no_prelude() {
     assert_eq!(quote_used.to_string(), quoted.to_string());
 }
 
+#[test]
+fn strict() {
+    let quoted = quote! {
+        ::serde::Serialize;
+        Some(10)
+    };
+
+    let quote_used = quote_use! {
+        # use strict;
+        # use no_prelude;
+        # use ::serde;
+
+        serde::Serialize;
+        Some(10)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
 #[test]
 fn prelude_core() {
     let quoted = quote! {
@@ -71,6 +91,55 @@ fn prelude_2021() {
     assert_eq!(quote_used.to_string(), quoted.to_string());
 }
 
+#[test]
+fn no_2021_prelude() {
+    let quoted = quote! {
+        FromIterator::from_iter(::core::prelude::v1::None)
+    };
+
+    let quote_used = quote_use! {
+        # use no_2021_prelude;
+        FromIterator::from_iter(None)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quote_used = quote_use_no_2021_prelude! {
+        FromIterator::from_iter(None)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn prelude_no_std_edition() {
+    let quoted = quote! {
+        ::core::prelude::rust_2021::FromIterator::from_iter(::core::prelude::v1::None);
+        <_ as ::core::prelude::rust_2021::TryFrom<u8>>::try_from(1);
+        Future
+    };
+
+    let quote_used = quote_use! {
+        # use no_std;
+        FromIterator::from_iter(None);
+        <_ as TryFrom<u8>>::try_from(1);
+        Future
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        FromIterator;
+        ::core::prelude::rust_2024::Future
+    };
+
+    let quote_used = quote_use! {
+        # use no_std;
+        # use no_2021_prelude;
+        # use edition(2024);
+        FromIterator;
+        Future
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
 #[test]
 fn prelude_std() {
     let quoted = quote! {
@@ -83,6 +152,41 @@ fn prelude_std() {
     assert_eq!(quote_used.to_string(), quoted.to_string());
 }
 
+#[test]
+fn prelude_alloc() {
+    let quoted = quote! {
+        String::new(vec![::core::prelude::v1::Some(10)])
+    };
+
+    let quote_used = quote_use! {
+        # use no_std;
+
+        String::new(vec![Some(10)])
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    // The `alloc` prelude matches the `std` prelude, i.e., it has no `vec!`.
+    let quoted = quote! {
+        ::alloc::string::String::new(vec![::core::prelude::v1::Some(10)])
+    };
+
+    let quote_used = quote_use! {
+        # use prelude(core, alloc);
+
+        String::new(vec![Some(10)])
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    // `std` is preferred.
+    let quote_used = quote_use! {
+        String::new(vec![])
+    };
+    let quoted = quote! {
+        ::std::prelude::v1::String::new(vec![])
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
 #[test]
 fn prelude_override() {
     let quoted = quote! {
@@ -238,3 +342,1687 @@ fn var_in_path() {
 
     assert_eq!(quote_used.to_string(), quoted.to_string());
 }
+
+#[test]
+fn const_static() {
+    let quoted = quote! {
+        const X: ::smth::ho::MyType = ::smth::ho::MyType::new();
+        static mut X: ::smth::ho::MyType = ::smth::ho::MyType::new();
+        let ptr: *const ::smth::ho::MyType = &::smth::ho::X;
+        const fn X() {}
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::{MyType, X};
+
+        const X: MyType = MyType::new();
+        static mut X: MyType = MyType::new();
+        let ptr: *const MyType = &X;
+        const fn X() {}
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn alias_shadows_segment() {
+    let quoted = quote! {
+        ::a::b::c;
+        ::a::b
+    };
+
+    let quote_used = quote_use! {
+        # use a::b as a;
+
+        a::c;
+        a
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn alias_in_path_and_method_position() {
+    let quoted = quote! {
+        let counter = ::std::sync::atomic::AtomicUsize::new(0);
+        counter.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+        <::std::sync::atomic::AtomicUsize as ::core::prelude::v1::Default>::default();
+        counter.Counter()
+    };
+
+    let quote_used = quote_use! {
+        # use std::sync::atomic::{AtomicUsize as Counter, Ordering};
+
+        let counter = Counter::new(0);
+        counter.fetch_add(1, Ordering::Relaxed);
+        <Counter as Default>::default();
+        counter.Counter()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn for_loop() {
+    let quoted = quote! {
+        for x in 0..::smth::ho::N {
+            ::core::prelude::v1::Some(x);
+        }
+        for ::core::prelude::v1::Some(x) in iter {}
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::N;
+
+        for x in 0..N {
+            Some(x);
+        }
+        for Some(x) in iter {}
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn self_and_names_in_group() {
+    let quoted = quote! {
+        ::a::thing();
+        ::a::Other
+    };
+
+    let quote_used = quote_use! {
+        # use a::{self, Other};
+
+        a::thing();
+        Other
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn where_self() {
+    let quoted = quote! {
+        fn f(self) where Self: ::smth::ho::MyTrait + ::core::prelude::v1::Sized {}
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::MyTrait;
+
+        fn f(self) where Self: MyTrait + Sized {}
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn trait_object() {
+    let quoted = quote! {
+        fn f() -> ::std::prelude::v1::Box<dyn ::smth::ho::MyTrait + ::core::prelude::v1::Send> {}
+        fn g() -> impl ::smth::ho::MyTrait + ::core::prelude::v1::Send {}
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::MyTrait;
+
+        fn f() -> Box<dyn MyTrait + Send> {}
+        fn g() -> impl MyTrait + Send {}
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn r#macro() {
+    let quoted = quote! {
+        let db = crate::db::Database::connection();
+        ::smth::ho::Name(crate::db::Database::connection())
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::Name;
+        # macro DB = crate::db::Database::connection();
+
+        let db = DB;
+        Name(DB)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn var_after_import() {
+    let method = quote::format_ident!("new");
+    let quoted = quote! {
+        ::smth::ho::Name::#method()
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::Name;
+
+        Name::#method()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+    assert_eq!(quote_used.to_string(), ":: smth :: ho :: Name :: new ()");
+}
+
+#[test]
+fn include() {
+    let quoted = quote! {
+        ::smth::ho::Name(10);
+        ::smth::ho::module::another::Strange;
+        ::other::Thing
+    };
+
+    let quote_used = quote_use! {
+        # use include("include/imports.rs");
+
+        Name(10);
+        Strange;
+        Renamed
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn tuple() {
+    let quoted = quote! {
+        let x: (::smth::ho::MyType, ::core::prelude::v1::Option<u8>) =
+            (::core::prelude::v1::Some(1), ::core::prelude::v1::None);
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::MyType;
+
+        let x: (MyType, Option<u8>) = (Some(1), None);
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn smart_pointer_constructors() {
+    let quoted = quote! {
+        ::std::prelude::v1::Box::new(x);
+        ::std::rc::Rc::new(x);
+        ::std::sync::Arc::new(x);
+        ::std::cell::RefCell::new(x);
+    };
+
+    // Only `Box` is part of the prelude.
+    let quote_used = quote_use! {
+        # use std::{rc::Rc, sync::Arc, cell::RefCell};
+
+        Box::new(x);
+        Rc::new(x);
+        Arc::new(x);
+        RefCell::new(x);
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn strict_interpolation() {
+    let Name = quote!(other::Name);
+    let quoted = quote! {
+        ::smth::ho::Name;
+        other::Name
+    };
+
+    // `Name` is also used outside of interpolation.
+    let quote_used = quote_use! {
+        # use strict_interpolation;
+        # use ::smth::ho::Name;
+
+        Name;
+        #Name
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn async_chain() {
+    let quoted = quote! {
+        client.foo().await?.bar(::core::prelude::v1::Some(::smth::ho::handler)).await?;
+        ::smth::ho::foo().await?;
+        for i in 0..::smth::ho::bar {}
+        for i in 0..=::smth::ho::bar {}
+        x.0.bar
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::{foo, bar, handler};
+
+        client.foo().await?.bar(Some(handler)).await?;
+        foo().await?;
+        for i in 0..bar {}
+        for i in 0..=bar {}
+        x.0.bar
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn append() {
+    let quoted = quote! {
+        ::smth::ho::Name(0);
+        ::smth::ho::Name(1);
+        ::smth::ho::Name(2);
+    };
+
+    let mut quote_used = proc_macro2::TokenStream::new();
+    for i in 0..3usize {
+        let i = proc_macro2::Literal::usize_unsuffixed(i);
+        quote_use_append! {&mut quote_used;
+            # use ::smth::ho::Name;
+
+            Name(#i);
+        };
+    }
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+#[allow(deprecated)]
+fn definition() {
+    let quoted = quote! {
+        struct Result;
+        fn read() -> ::core::prelude::v1::Result {}
+        enum Name {}
+    };
+
+    let quote_used = quote_use! {
+        # use ::std::fs::read;
+        # use ::smth::ho::Name;
+
+        struct Result;
+        fn read() -> Result {}
+        enum Name {}
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn relative_to() {
+    let quoted = quote! {
+        super::super::smth::ho::Name(::core::prelude::v1::Some(10))
+    };
+
+    let quote_used = quote_use! {
+        # use relative_to(super::super);
+        # use smth::ho::Name;
+
+        Name(Some(10))
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let root = quote!(crate::generated);
+    let quoted = quote! {
+        crate::generated::smth::ho::Name(10)
+    };
+
+    let quote_used = quote_use! {
+        # use relative_to(#root);
+        # use smth::ho::Name;
+
+        Name(10)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        $crate::foo::Bar(#root::Baz)
+    };
+
+    let quote_used = quote_use! {
+        # use relative_to($crate);
+        # use foo::Bar;
+        # use #root::Baz;
+
+        Bar(Baz)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn struct_variant_pattern() {
+    let quoted = quote! {
+        match value {
+            ::smth::ho::MyEnum::Variant { field, other: ::core::prelude::v1::Some(x) } => field,
+            ::smth::ho::MyEnum::Other { .. } => 0,
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::MyEnum;
+
+        match value {
+            MyEnum::Variant { field, other: Some(x) } => field,
+            MyEnum::Other { .. } => 0,
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn prelude_trait_path() {
+    let quoted = quote! {
+        let builder: Builder = ::core::prelude::v1::Default::default();
+        Builder {
+            name: ::core::prelude::v1::Default::default(),
+            ..::core::prelude::v1::Default::default()
+        }
+    };
+
+    let quote_used = quote_use! {
+        let builder: Builder = Default::default();
+        Builder { name: Default::default(), ..Default::default() }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn assert_macros() {
+    let quoted = quote! {
+        ::core::prelude::v1::assert!(x == ::core::prelude::v1::Some(1));
+        ::core::assert_eq!(x, ::core::prelude::v1::Some(1));
+        debug_assert!(::core::matches!(x, ::core::prelude::v1::None));
+    };
+
+    // `assert` is part of the prelude, `assert_eq` and `debug_assert` are not.
+    let quote_used = quote_use! {
+        # use core::{assert_eq, matches};
+
+        assert!(x == Some(1));
+        assert_eq!(x, Some(1));
+        debug_assert!(matches!(x, None));
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn std_macros() {
+    let quoted = quote! {
+        ::std::dbg!(::core::prelude::v1::Some(x));
+        eprintln!("{:?}", ::core::prelude::v1::Ok::<_, ::std::prelude::v1::String>(1));
+        ::core::panic!("{}", ::core::prelude::v1::Default::default());
+        unreachable!("{:?}", ::core::prelude::v1::None::<u8>)
+    };
+
+    // The macro names are not part of the prelude, but can be imported.
+    let quote_used = quote_use! {
+        # use std::dbg;
+        # use core::panic;
+
+        dbg!(Some(x));
+        eprintln!("{:?}", Ok::<_, String>(1));
+        panic!("{}", Default::default());
+        unreachable!("{:?}", None::<u8>)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn macros() {
+    let variant = |name| quote_use::MACROS.iter().find(|m| m.name == name).unwrap();
+    assert!(variant("quote_use").prelude);
+    assert!(!variant("quote_use_no_prelude").prelude);
+    assert!(variant("parse_quote_use_no_prelude").syn);
+    assert!(variant("quote_use_no_2021_prelude").prelude);
+}
+
+#[test]
+fn none_delimited_group() {
+    // `$expr:expr` is passed to `quote_use!` as a `None`-delimited group.
+    macro_rules! wrapped {
+        ($expr:expr) => {
+            quote_use! {
+                # use ::smth::ho::Name;
+
+                $expr
+            }
+        };
+    }
+
+    let quoted = quote! {
+        ::smth::ho::Name(::core::prelude::v1::Some(10))
+    };
+    assert_eq!(wrapped!(Name(Some(10))).to_string(), quoted.to_string());
+}
+
+#[test]
+fn interpolated_path_in_macro_rules() {
+    // `#root` and `#module` refer to the variables of `wrapped!`, while `Name`
+    // and `Other` are passed in by the caller, i.e., the interpolated tokens
+    // must not take the span of the replaced name.
+    macro_rules! wrapped {
+        ($($tokens:tt)*) => {{
+            let root = quote!(r);
+            let module = quote!(m);
+            quote_use! {
+                # use relative_to(#root);
+                # use a::Name;
+                # use #module::Other;
+
+                $($tokens)*
+            }
+        }};
+    }
+
+    let quoted = quote! {
+        r::a::Name(m::Other)
+    };
+    assert_eq!(wrapped!(Name(Other)).to_string(), quoted.to_string());
+}
+
+#[test]
+fn operator_traits() {
+    let quoted = quote! {
+        ::core::ops::Add::add(a, b);
+        ::core::ops::Mul::mul(a, b);
+        <T as ::core::ops::Neg>::neg(a)
+    };
+
+    let quote_used = quote_use! {
+        # use core::ops::{Add, Mul, Neg};
+
+        Add::add(a, b);
+        Mul::mul(a, b);
+        <T as Neg>::neg(a)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn import_group() {
+    let quoted = quote! {
+        ::a::B;
+        ::c::D
+    };
+
+    let quote_used = quote_use! {
+        # use group Common = {a::B, c::D};
+        # use Common;
+
+        B;
+        D
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    // Groups are only applied when used.
+    let quote_used = quote_use! {
+        # use group Common = {a::B, c::D};
+
+        B;
+        D
+    };
+    assert_eq!(quote_used.to_string(), "B ; D");
+}
+
+#[test]
+fn impl_default() {
+    let quoted = quote! {
+        impl ::core::prelude::v1::Default for ::smth::ho::MyType {
+            fn default() -> Self {
+                ::smth::ho::MyType { value: ::core::prelude::v1::None }
+            }
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use ::smth::ho::MyType;
+
+        impl Default for MyType {
+            fn default() -> Self {
+                MyType { value: None }
+            }
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn dollar_crate() {
+    let quoted = quote! {
+        $crate::smth::Name;
+        $crate;
+        ::other::Name
+    };
+
+    let quote_used = quote_use! {
+        # use dollar_crate(my_crate);
+        # use my_crate::smth::Name;
+        # use my_crate as krate;
+        # use other::Name as Other;
+
+        Name;
+        krate;
+        Other
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn slice_pattern() {
+    let quoted = quote! {
+        match slice {
+            [::core::prelude::v1::Some(first), .., ::core::prelude::v1::None] => first,
+            [rest @ ..] => rest,
+        }
+    };
+
+    let quote_used = quote_use! {
+        match slice {
+            [Some(first), .., None] => first,
+            [rest @ ..] => rest,
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn rewrite() {
+    let input = quote! {
+        Some(Vec::new())
+    };
+    assert_eq!(
+        quote_use::rewrite(input).unwrap().to_string(),
+        quote!(::core::prelude::v1::Some(::std::prelude::v1::Vec::new())).to_string()
+    );
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn rewrite_expr() {
+    let input: proc_macro2::TokenStream = "
+        # use std::collections::HashMap;
+
+        HashMap::<String, u8>::new()
+    "
+    .parse()
+    .unwrap();
+    let expected: syn::Expr = syn::parse_quote! {
+        ::std::collections::HashMap::<::std::prelude::v1::String, u8>::new()
+    };
+    assert_eq!(quote_use::rewrite_expr(input).unwrap(), expected);
+
+    let input: proc_macro2::TokenStream = "# use a::b; struct B;".parse().unwrap();
+    assert!(quote_use::rewrite_expr(input).is_err());
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn rewrite_type() {
+    let input: proc_macro2::TokenStream = "
+        # use std::sync::Arc;
+
+        Arc<Option<String>>
+    "
+    .parse()
+    .unwrap();
+    let expected: syn::Type = syn::parse_quote! {
+        ::std::sync::Arc<::core::prelude::v1::Option<::std::prelude::v1::String>>
+    };
+    assert_eq!(quote_use::rewrite_type(input).unwrap(), expected);
+}
+
+#[test]
+fn phantom_data_field() {
+    let quoted = quote! {
+        struct Foo<T> {
+            _p: ::std::marker::PhantomData<T>,
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use std::marker::PhantomData;
+
+        struct Foo<T> {
+            _p: PhantomData<T>,
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn binding_modifiers() {
+    let quoted = quote! {
+        if let &::core::prelude::v1::Some(x) = value {}
+        if let ::core::prelude::v1::Some(ref mut y) = value {}
+        matches!(value, &::core::prelude::v1::Some(ref z))
+    };
+
+    let quote_used = quote_use! {
+        if let &Some(x) = value {}
+        if let Some(ref mut y) = value {}
+        matches!(value, &Some(ref z))
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn nested_braces() {
+    let quoted = quote! {
+        { { ::core::prelude::v1::Some(1) } { [(::core::prelude::v1::None)] } }
+    };
+
+    let quote_used = quote_use! {
+        { { Some(1) } { [(None)] } }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn inline_const() {
+    let quoted = quote! {
+        let x = const { ::core::prelude::v1::Some(1) };
+    };
+
+    let quote_used = quote_use! {
+        let x = const { Some(1) };
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn namespaces() {
+    let quoted = quote! {
+        let x: ::a::Foo = ::b::Foo(1);
+        let y = ::a::Foo::new() as ::a::Foo;
+        fn f(foo: &::std::prelude::v1::Vec<::a::Foo>, bar: (u8, ::a::Foo)) -> ::a::Foo {
+            ::b::Foo
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use type a::Foo;
+        # use value b::Foo;
+
+        let x: Foo = Foo(1);
+        let y = Foo::new() as Foo;
+        fn f(foo: &Vec<Foo>, bar: (u8, Foo)) -> Foo {
+            Foo
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn extern_fn_pointer() {
+    let quoted = quote! {
+        type Callback = unsafe extern "C" fn(x: ::a::MyType) -> ::a::Other;
+    };
+
+    let quote_used = quote_use! {
+        # use a::{MyType, Other};
+
+        type Callback = unsafe extern "C" fn(x: MyType) -> Other;
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn rewriter() {
+    use proc_macro2::{Ident, Span};
+    use quote_use::{Directives, Rewriter, Use};
+
+    let ident = |name| Ident::new(name, Span::call_site());
+    let rewriter = Rewriter::with_directives(Directives::default().no_std(true), vec![
+        Use::new([ident("std"), ident("sync"), ident("Arc")]),
+        Use::with_alias([ident("a"), ident("Foo")], ident("Bar")),
+    ]);
+    assert_eq!(
+        rewriter
+            .rewrite(quote!(Arc<Bar>::new(Some(Vec::new()))))
+            .unwrap()
+            .to_string(),
+        quote!(::std::sync::Arc<::a::Foo>::new(::core::prelude::v1::Some(Vec::new()))).to_string()
+    );
+
+    let rewriter = Rewriter::with_directives(
+        Directives::default()
+            .no_prelude(true)
+            .relative_to(quote!(crate)),
+        vec![Use::new([ident("generated"), ident("Foo")])],
+    );
+    assert_eq!(
+        rewriter.rewrite(quote!(Foo(None))).unwrap().to_string(),
+        quote!(crate::generated::Foo(None)).to_string()
+    );
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn replace_uses() {
+    use quote_use::Use;
+    use syn::parse::Parser;
+
+    let uses = Use::parse_item
+        .parse2(quote!(
+            use std::{sync::Arc, collections::HashMap as Map};
+        ))
+        .unwrap();
+    assert_eq!(uses[1].name(), "Map");
+    assert_eq!(
+        uses[1].path().to_string(),
+        quote!(::std::collections::HashMap).to_string()
+    );
+    assert_eq!(
+        quote_use::replace_uses(&uses, quote!(Arc<Map<String, Option<u8>>>)).to_string(),
+        quote!(::std::sync::Arc<::std::collections::HashMap<String, Option<u8>>>).to_string()
+    );
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn with_imports() {
+    use quote::format_ident;
+
+    let mut imports: Vec<(syn::Path, _)> = ["HashMap", "HashSet"]
+        .into_iter()
+        .map(|name| {
+            let name = format_ident!("{name}");
+            (syn::parse_quote!(std::collections::#name), name)
+        })
+        .collect();
+    imports.push((syn::parse_quote!(crate::Value), format_ident!("V")));
+    imports.push((syn::parse_quote!(::std::vec::Vec), format_ident!("Bytes")));
+    assert_eq!(
+        quote_use::with_imports(imports, quote! {
+            HashMap<V, HashSet<Bytes<u8>>>
+        })
+        .unwrap()
+        .to_string(),
+        quote! {
+            ::std::collections::HashMap<crate::Value, ::std::collections::HashSet<::std::vec::Vec<u8>>>
+        }
+        .to_string()
+    );
+
+    // Generic arguments cannot be imported.
+    let imports: [(syn::Path, _); 1] = [(
+        syn::parse_quote!(::std::vec::Vec<u8>),
+        format_ident!("Bytes"),
+    )];
+    assert_eq!(
+        quote_use::with_imports(imports, quote!(Bytes))
+            .unwrap_err()
+            .to_string(),
+        "generic arguments are not supported in imports"
+    );
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn rewriter_groups() {
+    use proc_macro2::{Ident, Span};
+    use quote_use::{Directives, Rewriter, Use};
+
+    let ident = |name| Ident::new(name, Span::call_site());
+    let rewriter = Rewriter::with_directives(Directives::default(), vec![Use::new([
+        ident("std"),
+        ident("sync"),
+        ident("Arc"),
+    ])]);
+    let input = quote! {
+        impl Trait for Struct {
+            fn method(&self, value: Arc<[u8]>) -> Option<Vec<u8>> {
+                Some(value.iter().copied().collect())
+            }
+        }
+    };
+    assert_eq!(
+        rewriter.rewrite(input).unwrap().to_string(),
+        quote! {
+            impl Trait for Struct {
+                fn method(&self, value: ::std::sync::Arc<[u8]>)
+                    -> ::core::prelude::v1::Option<::std::prelude::v1::Vec<u8>>
+                {
+                    ::core::prelude::v1::Some(value.iter().copied().collect())
+                }
+            }
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn collect_turbofish() {
+    let quoted = quote! {
+        iter.collect::<::a::MyVec<_>>()
+    };
+
+    let quote_used = quote_use! {
+        # use a::MyVec;
+
+        iter.collect::<MyVec<_>>()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn rewrite_normalized() {
+    let input: proc_macro2::TokenStream = "
+        # use std::sync::Arc;
+
+        Arc  ::new( Some (1) ) .clone ( )
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(
+        quote_use::rewrite_normalized(input).unwrap(),
+        quote!(::std::sync::Arc::new(::core::prelude::v1::Some(1)).clone()).to_string()
+    );
+}
+
+#[test]
+fn labeled_break() {
+    let quoted = quote! {
+        'outer: loop {
+            break 'outer ::core::prelude::v1::Some(1);
+        }
+    };
+
+    let quote_used = quote_use! {
+        'outer: loop {
+            break 'outer Some(1);
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn module_function() {
+    let quoted = quote! {
+        ::core::mem::swap(a, b);
+        ::core::ptr::null::<u8>()
+    };
+
+    let quote_used = quote_use! {
+        # use core::{mem, ptr};
+
+        mem::swap(a, b);
+        ptr::null::<u8>()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn edition() {
+    let quoted = quote! {
+        FromIterator::from_iter(::core::prelude::v1::Some(1))
+    };
+
+    let quote_used = quote_use! {
+        # use edition(2015);
+
+        FromIterator::from_iter(Some(1))
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        fn f() -> impl ::core::prelude::rust_2024::Future<Output = ()> {}
+        ::core::prelude::rust_2021::FromIterator
+    };
+
+    let quote_used = quote_use! {
+        # use edition(2024);
+
+        fn f() -> impl Future<Output = ()> {}
+        FromIterator
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn try_operator() {
+    let quoted = quote! {
+        fn f<T, E>(x: ::core::prelude::v1::Result<T, E>) -> ::core::prelude::v1::Result<::core::prelude::v1::Option<T>, E> {
+            let x = x?;
+            ::core::prelude::v1::Ok(::core::prelude::v1::Some(x))
+        }
+    };
+
+    let quote_used = quote_use! {
+        fn f<T, E>(x: Result<T, E>) -> Result<Option<T>, E> {
+            let x = x?;
+            Ok(Some(x))
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn super_alias() {
+    let quoted = quote! {
+        super::module::Thing()
+    };
+
+    let quote_used = quote_use! {
+        # use super::module as m;
+
+        m::Thing()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn substitution_stats() {
+    let input: proc_macro2::TokenStream = "
+        # use std::sync::Arc;
+        # use a::Unused;
+
+        Arc::new(Some(Arc::new(Some(1)))).read(#Arc)
+    "
+    .parse()
+    .unwrap();
+    let mut stats: Vec<_> = quote_use::substitution_stats(input)
+        .unwrap()
+        .into_iter()
+        .map(|(ident, count)| (ident.to_string(), count))
+        .collect();
+    stats.sort();
+    assert_eq!(stats, [("Arc".to_owned(), 2), ("Some".to_owned(), 2)]);
+}
+
+#[test]
+fn aliased_method_chain() {
+    let quoted = quote! {
+        ::a::Builder::new().finish()
+    };
+
+    let quote_used = quote_use! {
+        # use a::Builder as B;
+
+        B::new().finish()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn raw_string() {
+    let quoted = quote! {
+        (r#"Some"#, b"Some", ::a::Some, ::a::Some)
+    };
+
+    let quote_used = quote_use! {
+        # use a::Some;
+
+        (r#"Some"#, b"Some", r#Some, Some)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn method_call() {
+    let quoted = quote! {
+        f.read();
+        a.read().write();
+        (x.0, ::std::fs::read(x.1))
+    };
+
+    let quote_used = quote_use! {
+        # use std::fs::read;
+        # use std::io::write;
+
+        f.read();
+        a.read().write();
+        (x.0, read(x.1))
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn field_names() {
+    let quoted = quote! {
+        let s = Struct { read: 5, other: ::std::fs::read };
+        let Struct { read: x, .. } = s;
+        impl A {
+            fn f() where ::a::Foo: ::core::prelude::v1::Clone {}
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use std::fs::read;
+        # use a::Foo;
+
+        let s = Struct { read: 5, other: read };
+        let Struct { read: x, .. } = s;
+        impl A {
+            fn f() where Foo: Clone {}
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn match_guard() {
+    let quoted = quote! {
+        match x {
+            ::core::prelude::v1::Some(x) if x > ::a::LIMIT => true,
+            _ => false,
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use a::LIMIT;
+
+        match x {
+            Some(x) if x > LIMIT => true,
+            _ => false,
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+#[allow(deprecated)]
+fn lock_prelude() {
+    let quoted = quote! {
+        ::core::prelude::v1::Some(::a::Other)
+    };
+
+    let quote_used = quote_use! {
+        # use lock_prelude;
+        # use a::{Some, Other};
+
+        Some(Other)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn underscore_alias() {
+    let quoted = quote! {
+        let _ = ::other::Trait::method(x);
+        Ext
+    };
+
+    let quote_used = quote_use! {
+        # use smth::Trait as _;
+        # use other::{Trait, Ext as _};
+
+        let _ = Trait::method(x);
+        Ext
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn asm_operands() {
+    let quoted = quote! {
+        asm!("mov {0}, {1}", out(reg) x, in(reg) ::a::SOME_CONST, const ::a::SOME_CONST);
+    };
+
+    let quote_used = quote_use! {
+        # use a::SOME_CONST;
+
+        asm!("mov {0}, {1}", out(reg) x, in(reg) SOME_CONST, const SOME_CONST);
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn top_level_group() {
+    let quoted = quote! {
+        ::std::fs::read(::smth::ho::x).write(::std::io::Write::flush).read(::std::io::Read)
+    };
+
+    let quote_used = quote_use! {
+        # use {std::fs::read, std::io::{Write as Wr, Read as Re}, ::smth::ho,};
+
+        read(ho::x).write(Wr::flush).read(Re)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn impl_where_binding() {
+    let quoted = quote! {
+        impl<T> ::a::MyTrait for Wrapper<T>
+        where
+            T: ::core::prelude::v1::Iterator<Item = ::a::MyType>
+        {}
+    };
+
+    let quote_used = quote_use! {
+        # use a::{MyType, MyTrait};
+
+        impl<T> MyTrait for Wrapper<T> where T: Iterator<Item = MyType> {}
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn keep() {
+    let quoted = quote! {
+        drop(Some(::core::prelude::v1::None));
+        Name
+    };
+
+    let quote_used = quote_use! {
+        # use keep drop;
+        # use keep Some, Name;
+        # use a::Name;
+
+        drop(Some(None));
+        Name
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    // `#keep` is an interpolation, not a statement.
+    let keep = quote!(x);
+    let quoted = quote! {
+        x + 1
+    };
+
+    let quote_used = quote_use! {
+        #keep + 1
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+#[cfg(feature = "syn")]
+fn directives() {
+    assert_eq!(quote_use::DIRECTIVES, [
+        "no_prelude",
+        "no_std",
+        "strict",
+        "strict_interpolation",
+        "lock_prelude",
+        "prefer_std",
+        "no_2021_prelude",
+        "warn_unused",
+        "no_leading_colon",
+    ]);
+}
+
+#[test]
+fn no_leading_colon() {
+    let quoted = quote! {
+        ::smth::ho::Name::new(crate::Local, ::core::prelude::v1::Some(1))
+    };
+
+    let quote_used = quote_use! {
+        # use smth::ho::Name;
+        # use crate::Local;
+
+        Name::new(Local, Some(1))
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        smth::ho::Name::new(crate::Local, core::prelude::v1::Some(1))
+    };
+
+    let quote_used = quote_use! {
+        # use no_leading_colon;
+        # use smth::ho::Name;
+        # use crate::Local;
+
+        Name::new(Local, Some(1))
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        my::smth::Name
+    };
+
+    let quote_used = quote_use! {
+        # use no_leading_colon;
+        # use relative_to(my);
+        # use smth::Name;
+
+        Name
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn raw_identifiers() {
+    let quoted = quote! {
+        ::foo::r#type();
+        ::foo::r#match(async_fn);
+        ::foo::bar::r#async::run();
+        async_fn
+    };
+
+    let quote_used = quote_use! {
+        # use foo::r#type;
+        # use foo::r#match as matcher;
+        # use foo::bar::r#async;
+
+        r#type();
+        matcher(async_fn);
+        r#async::run();
+        async_fn
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        ::foo::r#bar(::foo::r#bar);
+        ::foo::baz(::foo::baz)
+    };
+
+    // `r#bar` and `bar` are the same name.
+    let quote_used = quote_use! {
+        # use foo::r#bar;
+        # use foo::baz;
+
+        bar(r#bar);
+        r#baz(baz)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn qualified_receiver() {
+    let quoted = quote! {
+        <::a::MyType>::associated();
+        <::a::MyType as ::a::Trait>::method(value)
+    };
+
+    let quote_used = quote_use! {
+        # use a::MyType;
+        # use a::Trait;
+
+        <MyType>::associated();
+        <MyType as Trait>::method(value)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn visibility() {
+    let quoted = quote! {
+        (::a::Name, ::b::Other, ::b::Third)
+    };
+
+    let quote_used = quote_use! {
+        # pub use a::Name;
+        # pub(crate) use b::{Other, Third};
+
+        (Name, Other, Third)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn extern_crate() {
+    let quoted = quote! {
+        impl ::serde::Deserialize for crate::Name {}
+    };
+
+    let quote_used = quote_use! {
+        # extern crate serde as s;
+        # extern crate self as this;
+
+        impl s::Deserialize for this::Name {}
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn nested_std_generics() {
+    let quoted = quote! {
+        struct Containers<'a> {
+            name: ::std::borrow::Cow<'a, str>,
+            bytes: ::std::prelude::v1::Box<[u8]>,
+            handlers: ::std::prelude::v1::Vec<::std::prelude::v1::Box<
+                dyn ::core::prelude::v1::Fn(&'a str) -> ::core::prelude::v1::Option<u8> + 'a
+            >>,
+            nested: ::core::prelude::v1::Option<
+                ::std::prelude::v1::Vec<::std::borrow::Cow<'static, [u16]>>
+            >,
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use std::borrow::Cow;
+
+        struct Containers<'a> {
+            name: Cow<'a, str>,
+            bytes: Box<[u8]>,
+            handlers: Vec<Box<dyn Fn(&'a str) -> Option<u8> + 'a>>,
+            nested: Option<Vec<Cow<'static, [u16]>>>,
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn generated_test() {
+    let input = "input";
+    let expected = 1;
+
+    let quoted = quote! {
+        #[::core::prelude::v1::test]
+        fn parses() {
+            let result = crate::parse(#input);
+            assert_eq!(
+                result,
+                ::core::prelude::v1::Ok(::core::prelude::v1::Some(#expected))
+            );
+        }
+    };
+
+    let quote_used = quote_use! {
+        # use crate::parse;
+
+        #[test]
+        fn parses() {
+            let result = parse(#input);
+            assert_eq!(result, Ok(Some(#expected)));
+        }
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+#[allow(deprecated)]
+fn custom_prelude() {
+    let quoted = quote! {
+        (
+            ::core::prelude::v1::Option,
+            ::my_crate::Result,
+            ::my_crate::Name,
+            crate::Other
+        )
+    };
+
+    let quote_used = quote_use! {
+        # use prelude my_crate::{Result, Name};
+        # use prelude crate::Other;
+
+        (Option, Result, Name, Other)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        (::anyhow::Result, ::my_crate::Name)
+    };
+
+    // Imports take precedence over the custom prelude.
+    let quote_used = quote_use! {
+        # use prelude my_crate::{Result, Name};
+        # use anyhow::Result;
+
+        (Result, Name)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        (::core::prelude::v1::Option, ::my_crate::Name)
+    };
+
+    // `lock_prelude` includes the custom prelude.
+    let quote_used = quote_use! {
+        # use lock_prelude;
+        # use prelude my_crate::{Option, Name};
+        # use other::Name;
+
+        (Option, Name)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn prefer_std() {
+    let quoted = quote! {
+        (
+            ::std::prelude::v1::Some,
+            ::std::prelude::v1::String,
+            ::std::prelude::rust_2021::FromIterator
+        )
+    };
+
+    let quote_used = quote_use! {
+        # use prefer_std;
+
+        (Some, String, FromIterator)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        ::core::prelude::v1::Some
+    };
+
+    // Without `std`, there is nothing to prefer.
+    let quote_used = quote_use! {
+        # use prefer_std;
+        # use no_std;
+
+        Some
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        std::prelude::v1::Some
+    };
+
+    let quote_used = quote_use! {
+        # use prefer_std;
+        # use no_leading_colon;
+
+        Some
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn macro_call() {
+    let quoted = quote! {
+        ::serde_json::json!({ "key": ::serde_json::json });
+        ::my_crate::path::to::json!()
+    };
+
+    let quote_used = quote_use! {
+        # use serde_json::json;
+        # use my_crate::path;
+
+        json!({ "key": json });
+        path::to::json!()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn repetition() {
+    let items = [quote!(a), quote!(b)];
+    let values = [1, 2];
+
+    let quoted = quote! {
+        struct Fields {
+            #(#items: ::std::prelude::v1::Vec<::std::collections::HashMap<
+                ::std::prelude::v1::String,
+                u8
+            >>,)*
+        }
+        #(let #items = ::core::prelude::v1::Some(#values);)*
+        fn new(#(#items: ::core::prelude::v1::Option<u8>),*) -> Fields {
+            Fields { #(#items: vec![#items.unwrap()]),* }
+        }
+        const MAP: ::std::collections::HashMap<u8, u8> = ::std::collections::HashMap::new();
+        #(#values)* const HashMap: u8 = 1;
+        #(#values),* static HashMap: u8 = 1;
+    };
+
+    let quote_used = quote_use! {
+        # use std::collections::HashMap;
+
+        struct Fields {
+            #(#items: Vec<HashMap<String, u8>>,)*
+        }
+        #(let #items = Some(#values);)*
+        fn new(#(#items: Option<u8>),*) -> Fields {
+            Fields { #(#items: vec![#items.unwrap()]),* }
+        }
+        const MAP: HashMap<u8, u8> = HashMap::new();
+        #(#values)* const HashMap: u8 = 1;
+        #(#values),* static HashMap: u8 = 1;
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn select_prelude() {
+    let quoted = quote! {
+        (::core::prelude::v1::Some, String, TryFrom)
+    };
+
+    let quote_used = quote_use! {
+        # use prelude(core);
+
+        (Some, String, TryFrom)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        (::core::prelude::v1::Some, ::std::prelude::v1::String, TryFrom)
+    };
+
+    let quote_used = quote_use! {
+        # use prelude(std, core);
+
+        (Some, String, TryFrom)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        (
+            ::core::prelude::v1::Some,
+            ::alloc::string::String,
+            ::core::prelude::rust_2021::TryFrom
+        )
+    };
+
+    let quote_used = quote_use! {
+        # use prelude(core, alloc, rust_2021);
+
+        (Some, String, TryFrom)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        (Some, String)
+    };
+
+    let quote_used = quote_use! {
+        # use prelude(none);
+
+        (Some, String)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn precedence() {
+    let quoted = quote! {
+        let x: ::types::Name = ::values::Name(
+            ::my::Option::None,
+            ::custom::Result::Ok,
+            ::core::prelude::v1::Some
+        );
+    };
+
+    let quote_used = quote_use! {
+        # use prelude custom::{Option, Result};
+        # use my::Option;
+        # use type types::Name;
+        # use value values::Name;
+
+        let x: Name = Name(Option::None, Result::Ok, Some);
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn relative_roots() {
+    let quoted = quote! {
+        (crate::a::Krate, self::b::Module, super::c::Parent, Self::Variant)
+    };
+
+    let quote_used = quote_use! {
+        # use crate::a::Krate;
+        # use self::b::Module;
+        # use super::c::Parent;
+        # use Self::Variant;
+
+        (Krate, Module, Parent, Variant)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+
+    let quoted = quote! {
+        (crate::a::Krate, super::c::Parent, my::d::Other)
+    };
+
+    // `relative_to` only applies to imports rooted at `::`.
+    let quote_used = quote_use! {
+        # use relative_to(my);
+        # use crate::a::Krate;
+        # use super::c::Parent;
+        # use d::Other;
+
+        (Krate, Parent, Other)
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[test]
+fn turbofish_generics() {
+    let quoted = quote! {
+        ::std::vec::Vec::<u8>::new();
+        ::std::vec::Vec::<::core::prelude::v1::Option<::a::Name>>::with_capacity(1);
+        ::std::vec::Vec::<::core::prelude::v1::Result<
+            ::std::vec::Vec<::a::Name>,
+            ::std::prelude::v1::String
+        >>::new()
+    };
+
+    let quote_used = quote_use! {
+        # use std::vec::Vec;
+        # use a::Name;
+
+        Vec::<u8>::new();
+        Vec::<Option<Name>>::with_capacity(1);
+        Vec::<Result<Vec<Name>, String>>::new()
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[cfg(feature = "debug")]
+#[test]
+#[allow(deprecated)]
+fn debug() {
+    let quoted = quote! {
+        ::a::Name(::core::prelude::v1::Some(1))
+    };
+
+    let quote_used = quote_use::quote_use_debug! {
+        # use a::Name;
+
+        Name(Some(1))
+    };
+    assert_eq!(quote_used.to_string(), quoted.to_string());
+}
+
+#[cfg(feature = "syn")]
+#[test]
+fn applied_imports() {
+    use proc_macro2::{Ident, Span};
+    use quote_use::{Directives, Rewriter, Use};
+
+    let ident = |name| Ident::new(name, Span::call_site());
+    let rewriter = Rewriter::with_directives(Directives::default(), vec![
+        Use::new([ident("a"), ident("Name")]),
+        Use::new([ident("a"), ident("Unused")]),
+    ]);
+    let applied: Vec<_> = rewriter
+        .applied_imports(quote!(Name(Some(1))))
+        .into_iter()
+        .map(|(name, path)| (name.to_string(), path.to_string()))
+        .collect();
+    assert_eq!(applied, [
+        ("Name".to_owned(), ":: a :: Name".to_owned()),
+        (
+            "Some".to_owned(),
+            ":: core :: prelude :: v1 :: Some".to_owned()
+        ),
+    ]);
+}
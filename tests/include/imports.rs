@@ -0,0 +1,2 @@
+use ::smth::ho::{Name, module::another::Strange};
+use other::Thing as Renamed;
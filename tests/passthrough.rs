@@ -0,0 +1,22 @@
+#![cfg(feature = "passthrough")]
+
+use quote::quote;
+use quote_use::quote_use;
+
+#[test]
+fn passthrough() {
+    assert_eq!(
+        quote_use! {
+            # use std::sync::Arc;
+            # macro DB = crate::db();
+            # use keep Arc;
+
+            Arc::new(Some(DB))
+        }
+        .to_string(),
+        quote! {
+            Arc::new(Some(DB))
+        }
+        .to_string()
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(not(feature = "passthrough"))]
+
 use proc_macro2::Span;
 use quote::quote_spanned;
 use quote_use::quote_spanned_use;
@@ -1,3 +1,5 @@
+#![cfg(not(feature = "passthrough"))]
+
 use trybuild2::TestCases;
 
 macro_rules! test_case {
@@ -18,7 +20,7 @@ fn ui() {
                 not a ;
             );
         },
-        "expected one of: `;`, `as`, `::`"
+        "use statement must end with `;`"
     );
     test_case!(
         t,
@@ -29,6 +31,220 @@ fn ui() {
                 not a ;
             );
         },
-        "expected `;`"
+        "use statement must end with `;`"
+    );
+    test_case!(
+        t,
+        "missing ; after alias",
+        {
+            quote_use::quote_use!(
+                # use hello as world
+                not a ;
+            );
+        },
+        "use statement must end with `;`"
+    );
+    test_case!(
+        t,
+        "duplicate import",
+        {
+            quote_use::quote_use!(
+                # use a::Name;
+                # use b::{Name, Other};
+            );
+        },
+        "the name `Name` is imported multiple times"
+    );
+    test_case!(
+        t,
+        "duplicate import previous",
+        {
+            quote_use::quote_use!(
+                # use a::Name;
+                # use b::{Name, Other};
+            );
+        },
+        "previous import of `Name` here"
+    );
+    test_case!(
+        t,
+        "glob",
+        {
+            quote_use::quote_use!(
+                # use smth::prelude::*;
+            );
+        },
+        "glob imports are not supported, as the imported names are unknown; import the used names \
+         explicitly instead"
+    );
+    test_case!(
+        t,
+        "glob in group",
+        {
+            quote_use::quote_use!(
+                # use a::{self, *};
+            );
+        },
+        "glob imports are not supported"
+    );
+    test_case!(
+        t,
+        "unknown directive",
+        {
+            quote_use::quote_use!(
+                # use strict;
+                # use no_prelud;
+            );
+        },
+        "unknown directive `no_prelud`, did you mean `no_prelude`?"
+    );
+    test_case!(
+        t,
+        "strict interpolation",
+        {
+            let Name = 1;
+            quote_use::quote_use!(
+                # use strict_interpolation;
+                # use a::Name;
+                #Name
+            );
+        },
+        "`Name` is only used as interpolation `#Name`"
+    );
+    test_case!(
+        t,
+        "dollar crate without name",
+        {
+            quote_use::quote_use!(
+                # use dollar_crate;
+            );
+        },
+        "`dollar_crate` needs the name of the crate"
+    );
+    test_case!(
+        t,
+        "unknown edition",
+        {
+            quote_use::quote_use!(
+                # use edition(2020);
+            );
+        },
+        "unknown edition, expected one of 2015, 2018, 2021 or 2024"
+    );
+    test_case!(
+        t,
+        "shadowed prelude",
+        {
+            #[deny(deprecated)]
+            let _ = quote_use::quote_use!(
+                struct Result;
+            );
+        },
+        "`Result` shadows the prelude"
+    );
+    test_case!(
+        t,
+        "locked prelude",
+        {
+            #[deny(deprecated)]
+            let _ = quote_use::quote_use!(
+                # use lock_prelude;
+                # use a::Some;
+
+                Some(1)
+            );
+        },
+        "`Some` is part of the prelude, which takes precedence over imports with `lock_prelude`"
+    );
+    test_case!(
+        t,
+        "strict single letter import",
+        {
+            quote_use::quote_use!(
+                # use strict;
+                # use a::T;
+            );
+        },
+        "`T` is commonly a generic parameter, which would be replaced as well"
+    );
+    test_case!(
+        t,
+        "trait only used for methods",
+        {
+            #[deny(deprecated)]
+            let _ = quote_use::quote_use!(
+                # use std::fmt::Write;
+
+                write!(f, "{}", x)
+            );
+        },
+        "`Write` is not brought into scope"
+    );
+    test_case!(
+        t,
+        "import with attribute",
+        {
+            quote_use::quote_use!(
+                # #[cfg(feature = "x")] use a::Name;
+            );
+        },
+        "attributes on `# use` are not supported"
+    );
+    test_case!(
+        t,
+        "unused import",
+        {
+            #[deny(deprecated)]
+            let _ = quote_use::quote_use!(
+                # use warn_unused;
+                # use a::{Used, Unused};
+
+                Used
+            );
+        },
+        "`Unused` is imported, but never used"
+    );
+    test_case!(
+        t,
+        "unknown prelude",
+        {
+            quote_use::quote_use!(
+                # use prelude(core, rust_2018);
+            );
+        },
+        "unknown prelude, expected one of"
+    );
+    test_case!(
+        t,
+        "interpolated import name",
+        {
+            quote_use::quote_use!(
+                # use a::#b;
+            );
+        },
+        "interpolated `#b` cannot be used as an import name; add `as <ident>`"
+    );
+    #[cfg(feature = "debug")]
+    test_case!(
+        t,
+        "debug imports",
+        {
+            #[deny(deprecated)]
+            let _ = quote_use::quote_use_debug!(
+                # use a::Name;
+
+                Name(Some(1))
+            );
+        },
+        "applied imports: `Name` -> `::a::Name`, `Some` -> `::core::prelude::v1::Some`"
+    );
+    #[cfg(not(feature = "syn"))]
+    test_case!(
+        t,
+        "parse without syn",
+        {
+            let _: syn::Expr = quote_use::parse_quote_use!(a);
+        },
+        "enable the `syn` feature of `quote-use` to use `parse_quote_use!`"
     );
 }